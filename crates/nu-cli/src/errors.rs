@@ -51,3 +51,10 @@ pub fn report_error(
 ) {
     eprintln!("Error: {:?}", CliError(error, working_set));
 }
+
+pub fn report_warning(
+    working_set: &StateWorkingSet,
+    warning: &(dyn miette::Diagnostic + Send + Sync + 'static),
+) {
+    eprintln!("Warning: {:?}", CliError(warning, working_set));
+}
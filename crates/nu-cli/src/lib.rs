@@ -4,6 +4,6 @@ mod syntax_highlight;
 mod validation;
 
 pub use completions::NuCompleter;
-pub use errors::report_error;
+pub use errors::{report_error, report_warning};
 pub use syntax_highlight::NuHighlighter;
 pub use validation::NuValidator;
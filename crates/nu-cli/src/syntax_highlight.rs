@@ -23,6 +23,10 @@ impl Highlighter for NuHighlighter {
         let mut last_seen_span = global_span_offset;
 
         for shape in &shapes {
+            // Sourced files are parsed along with the line, but their shapes are in those files
+            if shape.0.start < global_span_offset || shape.0.end > global_span_offset + line.len() {
+                continue;
+            }
             if shape.0.end <= last_seen_span {
                 // We've already output something for this span
                 // so just skip this one
@@ -42,6 +46,10 @@ impl Highlighter for NuHighlighter {
                 FlatShape::Custom(..) => output.push((Style::new().bold(), next_token)),
                 FlatShape::External => output.push((Style::new().bold(), next_token)),
                 FlatShape::ExternalArg => output.push((Style::new().bold(), next_token)),
+                FlatShape::GlobPattern => output.push((
+                    Style::new().fg(nu_ansi_term::Color::Cyan).bold(),
+                    next_token,
+                )),
                 FlatShape::Garbage => output.push((
                     Style::new()
                         .fg(nu_ansi_term::Color::White)
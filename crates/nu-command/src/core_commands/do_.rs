@@ -36,7 +36,8 @@ impl Command for Do {
             Value::Block { val: block_id, .. } => {
                 let engine_state = context.engine_state.borrow();
                 let block = engine_state.get_block(block_id);
-                eval_block(context, block, input)
+                let state = context.enter_scope();
+                eval_block(&state, block, input)
             }
             _ => Ok(Value::nothing()),
         }
@@ -0,0 +1,28 @@
+use nu_protocol::ast::Call;
+use nu_protocol::engine::{Command, EvaluationContext};
+use nu_protocol::{Signature, Value};
+
+pub struct Export;
+
+impl Command for Export {
+    fn name(&self) -> &str {
+        "export"
+    }
+
+    fn usage(&self) -> &str {
+        "Export custom commands, aliases or environment variables from a module"
+    }
+
+    fn signature(&self) -> nu_protocol::Signature {
+        Signature::build("export")
+    }
+
+    fn run(
+        &self,
+        _context: &EvaluationContext,
+        call: &Call,
+        _input: Value,
+    ) -> Result<nu_protocol::Value, nu_protocol::ShellError> {
+        Ok(Value::Nothing { span: call.head })
+    }
+}
@@ -0,0 +1,53 @@
+use nu_protocol::ast::{Call, Expr, Expression};
+use nu_protocol::engine::{Command, EvaluationContext};
+use nu_protocol::{Signature, SyntaxShape, Value};
+
+pub struct Hide;
+
+impl Command for Hide {
+    fn name(&self) -> &str {
+        "hide"
+    }
+
+    fn usage(&self) -> &str {
+        "Hide definitions in the current scope"
+    }
+
+    fn signature(&self) -> nu_protocol::Signature {
+        Signature::build("hide").required(
+            "pattern",
+            SyntaxShape::ImportPattern,
+            "definition, variable or module members to hide",
+        )
+    }
+
+    fn run(
+        &self,
+        context: &EvaluationContext,
+        call: &Call,
+        _input: Value,
+    ) -> Result<nu_protocol::Value, nu_protocol::ShellError> {
+        // The parser already removed the definitions from the scope, only the environment
+        // variables exported by a module are left
+        if let Some(Expression {
+            expr: Expr::ImportPattern(import_pattern),
+            ..
+        }) = call.positional.first()
+        {
+            if let Some(block_id) = import_pattern.module_id {
+                let (overlay, _) = context
+                    .engine_state
+                    .borrow()
+                    .get_block(block_id)
+                    .exports
+                    .select(import_pattern);
+
+                for (name, _) in overlay.env_vars {
+                    context.hide_env_var(String::from_utf8_lossy(&name).to_string());
+                }
+            }
+        }
+
+        Ok(Value::Nothing { span: call.head })
+    }
+}
@@ -0,0 +1,40 @@
+use nu_protocol::ast::Call;
+use nu_protocol::engine::{Command, EvaluationContext};
+use nu_protocol::{ShellError, Signature, SyntaxShape, Value};
+
+pub struct HideEnv;
+
+impl Command for HideEnv {
+    fn name(&self) -> &str {
+        "hide env"
+    }
+
+    fn usage(&self) -> &str {
+        "Hide an environment variable in the current scope"
+    }
+
+    fn signature(&self) -> nu_protocol::Signature {
+        Signature::build("hide env").required(
+            "name",
+            SyntaxShape::String,
+            "environment variable to hide",
+        )
+    }
+
+    fn run(
+        &self,
+        context: &EvaluationContext,
+        call: &Call,
+        _input: Value,
+    ) -> Result<nu_protocol::Value, nu_protocol::ShellError> {
+        let name = call
+            .positional
+            .first()
+            .and_then(|expr| expr.as_string())
+            .ok_or_else(|| ShellError::InternalError("Missing environment variable name".into()))?;
+
+        context.hide_env_var(name);
+
+        Ok(Value::Nothing { span: call.head })
+    }
+}
@@ -20,7 +20,13 @@ impl Command for If {
             .required("then_block", SyntaxShape::Block(Some(vec![])), "then block")
             .optional(
                 "else",
-                SyntaxShape::Keyword(b"else".to_vec(), Box::new(SyntaxShape::Expression)),
+                SyntaxShape::Keyword(
+                    b"else".to_vec(),
+                    Box::new(SyntaxShape::OneOf(vec![
+                        SyntaxShape::Block(Some(vec![])),
+                        SyntaxShape::Expression,
+                    ])),
+                ),
                 "optional else followed by else block",
             )
     }
@@ -1,15 +1,43 @@
 mod alias;
 mod def;
+mod def_env;
 mod do_;
+mod export;
+mod export_alias;
+mod export_def;
+mod export_def_env;
+mod export_env;
+mod hide;
+mod hide_env;
 mod if_;
 mod let_;
 mod module;
+mod overlay;
+mod overlay_add;
+mod overlay_list;
+mod overlay_remove;
+mod source;
+mod try_;
 mod use_;
 
 pub use alias::Alias;
 pub use def::Def;
+pub use def_env::DefEnv;
 pub use do_::Do;
+pub use export::Export;
+pub use export_alias::ExportAlias;
+pub use export_def::ExportDef;
+pub use export_def_env::ExportDefEnv;
+pub use export_env::ExportEnv;
+pub use hide::Hide;
+pub use hide_env::HideEnv;
 pub use if_::If;
 pub use let_::Let;
 pub use module::Module;
+pub use overlay::OverlayCommand;
+pub use overlay_add::OverlayAdd;
+pub use overlay_list::OverlayList;
+pub use overlay_remove::OverlayRemove;
+pub use source::Source;
+pub use try_::Try;
 pub use use_::Use;
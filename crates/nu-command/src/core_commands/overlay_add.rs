@@ -0,0 +1,65 @@
+use nu_engine::eval_block;
+use nu_protocol::ast::{Call, Expr, Expression};
+use nu_protocol::engine::{Command, EvaluationContext};
+use nu_protocol::{ShellError, Signature, SyntaxShape, Value};
+
+pub struct OverlayAdd;
+
+impl Command for OverlayAdd {
+    fn name(&self) -> &str {
+        "overlay add"
+    }
+
+    fn usage(&self) -> &str {
+        "Add definitions and environment variables of a module as a new overlay"
+    }
+
+    fn signature(&self) -> nu_protocol::Signature {
+        Signature::build("overlay add").required(
+            "name",
+            SyntaxShape::ImportPattern,
+            "module name or module file to add as an overlay",
+        )
+    }
+
+    fn run(
+        &self,
+        context: &EvaluationContext,
+        call: &Call,
+        _input: Value,
+    ) -> Result<nu_protocol::Value, nu_protocol::ShellError> {
+        let import_pattern = if let Some(Expression {
+            expr: Expr::ImportPattern(pat),
+            ..
+        }) = call.positional.first()
+        {
+            pat
+        } else {
+            return Err(ShellError::InternalError(
+                "Got something else than import pattern".into(),
+            ));
+        };
+
+        // Definitions were added by the parser, only the environment variables are left
+        let engine_state = context.engine_state.borrow();
+        let mut env_vars = vec![];
+
+        if let Some(block_id) = import_pattern.module_id {
+            let exports = &engine_state.get_block(block_id).exports;
+
+            for (name, block_id) in &exports.env_vars {
+                let name = String::from_utf8_lossy(name).to_string();
+                let block = engine_state.get_block(*block_id);
+
+                let val = eval_block(context, block, Value::nothing())?;
+
+                env_vars.push((name, val));
+            }
+        }
+
+        let name = String::from_utf8_lossy(&import_pattern.head).to_string();
+        context.add_overlay_env(name, env_vars);
+
+        Ok(Value::Nothing { span: call.head })
+    }
+}
@@ -0,0 +1,42 @@
+use nu_protocol::ast::Call;
+use nu_protocol::engine::{Command, EvaluationContext};
+use nu_protocol::{Signature, Value};
+
+pub struct OverlayList;
+
+impl Command for OverlayList {
+    fn name(&self) -> &str {
+        "overlay list"
+    }
+
+    fn usage(&self) -> &str {
+        "List the active overlays, the most recent one last"
+    }
+
+    fn signature(&self) -> nu_protocol::Signature {
+        Signature::build("overlay list")
+    }
+
+    fn run(
+        &self,
+        context: &EvaluationContext,
+        call: &Call,
+        _input: Value,
+    ) -> Result<nu_protocol::Value, nu_protocol::ShellError> {
+        let overlays = context
+            .engine_state
+            .borrow()
+            .active_overlays()
+            .iter()
+            .map(|name| Value::String {
+                val: String::from_utf8_lossy(name).to_string(),
+                span: call.head,
+            })
+            .collect();
+
+        Ok(Value::List {
+            vals: overlays,
+            span: call.head,
+        })
+    }
+}
@@ -0,0 +1,41 @@
+use nu_protocol::ast::Call;
+use nu_protocol::engine::{Command, EvaluationContext};
+use nu_protocol::{ShellError, Signature, SyntaxShape, Value};
+
+pub struct OverlayRemove;
+
+impl Command for OverlayRemove {
+    fn name(&self) -> &str {
+        "overlay remove"
+    }
+
+    fn usage(&self) -> &str {
+        "Remove an overlay, restoring the definitions and environment variables it shadowed"
+    }
+
+    fn signature(&self) -> nu_protocol::Signature {
+        Signature::build("overlay remove").optional(
+            "name",
+            SyntaxShape::String,
+            "overlay to remove (the most recent one by default)",
+        )
+    }
+
+    fn run(
+        &self,
+        context: &EvaluationContext,
+        call: &Call,
+        _input: Value,
+    ) -> Result<nu_protocol::Value, nu_protocol::ShellError> {
+        // Definitions were removed by the parser, which also filled in the default name
+        let name = call
+            .positional
+            .first()
+            .and_then(|expr| expr.as_string())
+            .ok_or_else(|| ShellError::InternalError("Missing overlay name".into()))?;
+
+        context.remove_overlay_env(&name);
+
+        Ok(Value::Nothing { span: call.head })
+    }
+}
@@ -0,0 +1,43 @@
+use nu_engine::eval_block;
+use nu_protocol::ast::Call;
+use nu_protocol::engine::{Command, EvaluationContext};
+use nu_protocol::{ShellError, Signature, SyntaxShape, Value};
+
+pub struct Source;
+
+impl Command for Source {
+    fn name(&self) -> &str {
+        "source"
+    }
+
+    fn usage(&self) -> &str {
+        "Runs a script file in the current context"
+    }
+
+    fn signature(&self) -> nu_protocol::Signature {
+        Signature::build("source").required(
+            "filename",
+            SyntaxShape::String,
+            "the filepath to the script file to source",
+        )
+    }
+
+    fn run(
+        &self,
+        context: &EvaluationContext,
+        call: &Call,
+        input: Value,
+    ) -> Result<nu_protocol::Value, nu_protocol::ShellError> {
+        // The parser already parsed the file and left its block after the filename
+        let block_id = call
+            .positional
+            .get(1)
+            .and_then(|expr| expr.as_block())
+            .ok_or_else(|| ShellError::InternalError("Missing block of the sourced file".into()))?;
+
+        let engine_state = context.engine_state.borrow();
+        let block = engine_state.get_block(block_id);
+
+        eval_block(context, block, input)
+    }
+}
@@ -0,0 +1,54 @@
+use nu_engine::{eval_block, eval_expression};
+use nu_protocol::ast::Call;
+use nu_protocol::engine::{Command, EvaluationContext};
+use nu_protocol::{ShellError, Signature, SyntaxShape, Value};
+
+pub struct Try;
+
+impl Command for Try {
+    fn name(&self) -> &str {
+        "try"
+    }
+
+    fn usage(&self) -> &str {
+        "Run a block, ignoring the failure of an external command in it"
+    }
+
+    fn signature(&self) -> nu_protocol::Signature {
+        Signature::build("try").required(
+            "block",
+            SyntaxShape::Block(Some(vec![])),
+            "the block to run",
+        )
+    }
+
+    fn run(
+        &self,
+        context: &EvaluationContext,
+        call: &Call,
+        input: Value,
+    ) -> Result<nu_protocol::Value, nu_protocol::ShellError> {
+        // Only the errors of the block are caught, not the ones piped into it
+        if let Value::Error { error } = input {
+            return Err(error);
+        }
+
+        let block = &call.positional[0];
+
+        let out = eval_expression(context, block)?;
+
+        match out {
+            Value::Block { val: block_id, .. } => {
+                let engine_state = context.engine_state.borrow();
+                let block = engine_state.get_block(block_id);
+                let state = context.enter_scope();
+
+                match eval_block(&state, block, input) {
+                    Err(ShellError::ExternalCommand(..)) => Ok(Value::Nothing { span: call.head }),
+                    output => output,
+                }
+            }
+            _ => Ok(Value::nothing()),
+        }
+    }
+}
@@ -1,6 +1,7 @@
-use nu_protocol::ast::Call;
+use nu_engine::eval_block;
+use nu_protocol::ast::{Call, Expr, Expression};
 use nu_protocol::engine::{Command, EvaluationContext};
-use nu_protocol::{Signature, SyntaxShape, Value};
+use nu_protocol::{ShellError, Signature, SyntaxShape, Value};
 
 pub struct Use;
 
@@ -14,15 +15,46 @@ impl Command for Use {
     }
 
     fn signature(&self) -> nu_protocol::Signature {
-        Signature::build("use").required("module_name", SyntaxShape::String, "module name")
+        Signature::build("use").required("pattern", SyntaxShape::ImportPattern, "import pattern")
     }
 
     fn run(
         &self,
-        _context: &EvaluationContext,
+        context: &EvaluationContext,
         call: &Call,
         _input: Value,
     ) -> Result<nu_protocol::Value, nu_protocol::ShellError> {
+        let import_pattern = if let Some(Expression {
+            expr: Expr::ImportPattern(pat),
+            ..
+        }) = call.positional.first()
+        {
+            pat
+        } else {
+            return Err(ShellError::InternalError(
+                "Got something else than import pattern".into(),
+            ));
+        };
+
+        // Definitions were activated by the parser, only the environment variables are left
+        let engine_state = context.engine_state.borrow();
+
+        if let Some(block_id) = import_pattern.module_id {
+            let (overlay, _) = engine_state
+                .get_block(block_id)
+                .exports
+                .select(import_pattern);
+
+            for (name, block_id) in overlay.env_vars {
+                let name = String::from_utf8_lossy(&name).to_string();
+                let block = engine_state.get_block(block_id);
+
+                let val = eval_block(context, block, Value::nothing())?;
+
+                context.add_env_var(name, val);
+            }
+        }
+
         Ok(Value::Nothing { span: call.head })
     }
 }
@@ -6,10 +6,16 @@ use nu_protocol::{
 };
 
 use crate::{
-    Alias, Benchmark, BuildString, Def, Do, Each, External, For, Git, GitCheckout, If, Length, Let,
-    LetEnv, Lines, ListGitBranches, Ls, Module, Table, Use, Where,
+    Alias, Benchmark, BuildString, Cd, Complete, Def, DefEnv, Detect, DetectColumns, Do, Each,
+    Export, ExportAlias, ExportDef, ExportDefEnv, ExportEnv, External, For, From, FromCsv,
+    FromJson, FromNuon, FromSsv, FromToml, FromTsv, FromXml, FromYaml, Git, GitCheckout, GroupBy,
+    Hide, HideEnv, If, Length, Let, LetEnv, Lines, ListGitBranches, Ls, Module, Open, OverlayAdd,
+    OverlayCommand, OverlayList, OverlayRemove, Parse, Save, SortBy, Source, Table, To, ToCsv,
+    ToHtml, ToJson, ToMd, ToNuon, ToToml, ToTsv, ToXml, ToYaml, Try, Uniq, Use, Where,
 };
 
+use crate::path_conversion;
+
 pub fn create_default_context() -> Rc<RefCell<EngineState>> {
     let engine_state = Rc::new(RefCell::new(EngineState::new()));
     let delta = {
@@ -32,30 +38,120 @@ pub fn create_default_context() -> Rc<RefCell<EngineState>> {
 
         working_set.add_decl(Box::new(Def));
 
+        working_set.add_decl(Box::new(DefEnv));
+
+        working_set.add_decl(Box::new(Detect));
+
+        working_set.add_decl(Box::new(DetectColumns));
+
         working_set.add_decl(Box::new(For));
 
         working_set.add_decl(Box::new(Each));
 
         working_set.add_decl(Box::new(Where));
 
+        working_set.add_decl(Box::new(SortBy));
+
+        working_set.add_decl(Box::new(Uniq));
+
+        working_set.add_decl(Box::new(GroupBy));
+
         working_set.add_decl(Box::new(Do));
 
+        working_set.add_decl(Box::new(Try));
+
         working_set.add_decl(Box::new(Benchmark));
 
         working_set.add_decl(Box::new(Length));
 
         working_set.add_decl(Box::new(Ls));
 
+        working_set.add_decl(Box::new(Cd));
+
+        working_set.add_decl(Box::new(Open));
+
+        working_set.add_decl(Box::new(Save));
+
         working_set.add_decl(Box::new(Module));
 
         working_set.add_decl(Box::new(Use));
 
+        working_set.add_decl(Box::new(Export));
+
+        working_set.add_decl(Box::new(ExportDef));
+
+        working_set.add_decl(Box::new(ExportDefEnv));
+
+        working_set.add_decl(Box::new(ExportAlias));
+
+        working_set.add_decl(Box::new(ExportEnv));
+
+        working_set.add_decl(Box::new(Source));
+
+        working_set.add_decl(Box::new(Hide));
+
+        working_set.add_decl(Box::new(HideEnv));
+
+        working_set.add_decl(Box::new(OverlayCommand));
+
+        working_set.add_decl(Box::new(OverlayAdd));
+
+        working_set.add_decl(Box::new(OverlayRemove));
+
+        working_set.add_decl(Box::new(OverlayList));
+
         working_set.add_decl(Box::new(Table));
 
         working_set.add_decl(Box::new(External));
 
+        working_set.add_decl(Box::new(Complete));
+
         working_set.add_decl(Box::new(Lines));
 
+        working_set.add_decl(Box::new(Parse));
+
+        working_set.add_decl(Box::new(From));
+
+        working_set.add_decl(Box::new(FromCsv));
+
+        working_set.add_decl(Box::new(FromJson));
+
+        working_set.add_decl(Box::new(FromNuon));
+
+        working_set.add_decl(Box::new(FromSsv));
+
+        working_set.add_decl(Box::new(FromToml));
+
+        working_set.add_decl(Box::new(FromTsv));
+
+        working_set.add_decl(Box::new(FromXml));
+
+        working_set.add_decl(Box::new(FromYaml::yaml()));
+
+        working_set.add_decl(Box::new(FromYaml::yml()));
+
+        working_set.add_decl(Box::new(To));
+
+        working_set.add_decl(Box::new(ToCsv));
+
+        working_set.add_decl(Box::new(ToHtml));
+
+        working_set.add_decl(Box::new(ToJson));
+
+        working_set.add_decl(Box::new(ToMd));
+
+        working_set.add_decl(Box::new(ToNuon));
+
+        working_set.add_decl(Box::new(ToToml));
+
+        working_set.add_decl(Box::new(ToTsv));
+
+        working_set.add_decl(Box::new(ToXml));
+
+        working_set.add_decl(Box::new(ToYaml::yaml()));
+
+        working_set.add_decl(Box::new(ToYaml::yml()));
+
         // This is a WIP proof of concept
         working_set.add_decl(Box::new(ListGitBranches));
         working_set.add_decl(Box::new(Git));
@@ -78,7 +174,14 @@ pub fn create_default_context() -> Rc<RefCell<EngineState>> {
     };
 
     {
-        EngineState::merge_delta(&mut *engine_state.borrow_mut(), delta);
+        let mut engine_state = engine_state.borrow_mut();
+
+        EngineState::merge_delta(&mut engine_state, delta);
+
+        engine_state.add_env_conversion("PATH", path_conversion());
+        if cfg!(windows) {
+            engine_state.add_env_conversion("Path", path_conversion());
+        }
     }
 
     engine_state
@@ -0,0 +1,32 @@
+use nu_protocol::engine::EnvConversion;
+use nu_protocol::{ShellError, Value};
+
+/// Search paths are lists of directories, joined with the separator of the operating system
+pub fn path_conversion() -> EnvConversion {
+    EnvConversion {
+        from_string: |val, span| Value::List {
+            vals: std::env::split_paths(val)
+                .map(|path| Value::String {
+                    val: path.to_string_lossy().to_string(),
+                    span,
+                })
+                .collect(),
+            span,
+        },
+        to_string: |val| match val {
+            Value::List { vals, span } => {
+                let paths = vals
+                    .iter()
+                    .map(|path| path.as_string())
+                    .collect::<Result<Vec<_>, _>>()?;
+
+                let joined = std::env::join_paths(paths)
+                    .map_err(|_| ShellError::CantConvert("search path".into(), *span))?;
+
+                Ok(joined.to_string_lossy().to_string())
+            }
+            Value::String { val, .. } => Ok(val.clone()),
+            _ => Err(ShellError::CantConvert("search path".into(), val.span())),
+        },
+    }
+}
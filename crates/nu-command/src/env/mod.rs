@@ -1,3 +1,5 @@
+mod env_conversions;
 mod let_env;
 
+pub use env_conversions::path_conversion;
 pub use let_env::LetEnv;
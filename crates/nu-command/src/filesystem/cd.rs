@@ -0,0 +1,101 @@
+use std::path::PathBuf;
+
+use nu_engine::{current_dir, eval_expression, PWD_ENV};
+use nu_protocol::ast::Call;
+use nu_protocol::engine::{Command, EvaluationContext};
+use nu_protocol::{ShellError, Signature, SyntaxShape, Value};
+
+/// Environment variable with the directory `cd -` goes back to
+const OLDPWD_ENV: &str = "OLDPWD";
+
+pub struct Cd;
+
+impl Command for Cd {
+    fn name(&self) -> &str {
+        "cd"
+    }
+
+    fn usage(&self) -> &str {
+        "Change directory."
+    }
+
+    fn signature(&self) -> nu_protocol::Signature {
+        Signature::build("cd").optional(
+            "path",
+            SyntaxShape::String,
+            "the path to change to (home by default, - for the previous directory)",
+        )
+    }
+
+    fn run(
+        &self,
+        context: &EvaluationContext,
+        call: &Call,
+        _input: Value,
+    ) -> Result<nu_protocol::Value, nu_protocol::ShellError> {
+        let (path, span) = match call.positional.first() {
+            Some(expr) => (eval_expression(context, expr)?.as_string()?, expr.span),
+            None => ("~".into(), call.head),
+        };
+
+        let path = if path == "-" {
+            context
+                .stack
+                .get_env_var(OLDPWD_ENV)
+                .ok_or(ShellError::NoPreviousDirectory(span))?
+                .as_string()?
+        } else {
+            path
+        };
+
+        let cwd = current_dir(context)?;
+
+        let path = cwd
+            .join(expand_tilde(context, &path))
+            .canonicalize()
+            .map_err(|_| ShellError::DirectoryNotFound(span))?;
+
+        if !path.is_dir() {
+            return Err(ShellError::DirectoryNotFound(span));
+        }
+
+        // Like any other environment change, this only lasts until the end of the current scope
+        context.add_env_var(
+            OLDPWD_ENV.into(),
+            Value::String {
+                val: cwd.to_string_lossy().to_string(),
+                span: call.head,
+            },
+        );
+        context.add_env_var(
+            PWD_ENV.into(),
+            Value::String {
+                val: path.to_string_lossy().to_string(),
+                span: call.head,
+            },
+        );
+
+        Ok(Value::Nothing { span: call.head })
+    }
+}
+
+fn expand_tilde(context: &EvaluationContext, path: &str) -> PathBuf {
+    let home_env = if cfg!(windows) { "USERPROFILE" } else { "HOME" };
+
+    let home = match context
+        .stack
+        .get_env_var(home_env)
+        .and_then(|home| home.as_string().ok())
+    {
+        Some(home) => PathBuf::from(home),
+        None => return PathBuf::from(path),
+    };
+
+    if path == "~" {
+        home
+    } else if let Some(rest) = path.strip_prefix("~/") {
+        home.join(rest)
+    } else {
+        PathBuf::from(path)
+    }
+}
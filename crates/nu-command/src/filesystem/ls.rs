@@ -1,4 +1,6 @@
-use nu_engine::eval_expression;
+use std::path::Path;
+
+use nu_engine::{current_dir, eval_expression};
 use nu_protocol::ast::Call;
 use nu_protocol::engine::{Command, EvaluationContext};
 use nu_protocol::{IntoValueStream, Signature, SyntaxShape, Value};
@@ -37,53 +39,69 @@ impl Command for Ls {
         };
 
         let call_span = call.head;
-        let glob = glob::glob(&pattern).unwrap();
+
+        // Relative patterns are resolved against the working directory of the engine, but listed
+        // relative to it, as if the process itself was in that directory
+        let cwd = current_dir(context)?;
+        let is_relative = Path::new(&pattern).is_relative();
+        let pattern = Path::new(&glob::Pattern::escape(&cwd.to_string_lossy())).join(pattern);
+        let glob = glob::glob(&pattern.to_string_lossy()).unwrap();
 
         Ok(Value::Stream {
             stream: glob
                 .into_iter()
                 .map(move |x| match x {
-                    Ok(path) => match std::fs::symlink_metadata(&path) {
-                        Ok(metadata) => {
-                            let is_file = metadata.is_file();
-                            let is_dir = metadata.is_dir();
-                            let filesize = metadata.len();
+                    Ok(path) => {
+                        let name = if is_relative {
+                            path.strip_prefix(&cwd).unwrap_or(&path)
+                        } else {
+                            &path
+                        }
+                        .to_string_lossy()
+                        .to_string();
 
-                            Value::Record {
+                        match std::fs::symlink_metadata(&path) {
+                            Ok(metadata) => {
+                                let is_file = metadata.is_file();
+                                let is_dir = metadata.is_dir();
+                                let filesize = metadata.len();
+
+                                Value::Record {
+                                    cols: vec!["name".into(), "type".into(), "size".into()],
+                                    vals: vec![
+                                        Value::String {
+                                            val: name,
+                                            span: call_span,
+                                        },
+                                        if is_file {
+                                            Value::string("file", call_span)
+                                        } else if is_dir {
+                                            Value::string("dir", call_span)
+                                        } else {
+                                            Value::Nothing { span: call_span }
+                                        },
+                                        Value::Int {
+                                            val: filesize as i64,
+                                            span: call_span,
+                                        },
+                                    ],
+                                    span: call_span,
+                                }
+                            }
+                            Err(_) => Value::Record {
                                 cols: vec!["name".into(), "type".into(), "size".into()],
                                 vals: vec![
                                     Value::String {
-                                        val: path.to_string_lossy().to_string(),
-                                        span: call_span,
-                                    },
-                                    if is_file {
-                                        Value::string("file", call_span)
-                                    } else if is_dir {
-                                        Value::string("dir", call_span)
-                                    } else {
-                                        Value::Nothing { span: call_span }
-                                    },
-                                    Value::Int {
-                                        val: filesize as i64,
+                                        val: name,
                                         span: call_span,
                                     },
+                                    Value::Nothing { span: call_span },
+                                    Value::Nothing { span: call_span },
                                 ],
                                 span: call_span,
-                            }
+                            },
                         }
-                        Err(_) => Value::Record {
-                            cols: vec!["name".into(), "type".into(), "size".into()],
-                            vals: vec![
-                                Value::String {
-                                    val: path.to_string_lossy().to_string(),
-                                    span: call_span,
-                                },
-                                Value::Nothing { span: call_span },
-                                Value::Nothing { span: call_span },
-                            ],
-                            span: call_span,
-                        },
-                    },
+                    }
                     _ => Value::Nothing { span: call_span },
                 })
                 .into_value_stream(),
@@ -1,3 +1,9 @@
+mod cd;
 mod ls;
+mod open;
+mod save;
 
+pub use cd::Cd;
 pub use ls::Ls;
+pub use open::Open;
+pub use save::Save;
@@ -0,0 +1,98 @@
+use std::fs::File;
+use std::io::Read;
+
+use nu_engine::{current_dir, eval_call, eval_expression};
+use nu_protocol::ast::Call;
+use nu_protocol::engine::{Command, EvaluationContext};
+use nu_protocol::{DeclId, RawStream, ShellError, Signature, Span, SyntaxShape, Value};
+
+/// Files larger than this are streamed instead of being read into memory at once
+const STREAM_THRESHOLD: u64 = 1024 * 1024;
+
+pub struct Open;
+
+impl Command for Open {
+    fn name(&self) -> &str {
+        "open"
+    }
+
+    fn usage(&self) -> &str {
+        "Open a file, converting its contents based on the file extension."
+    }
+
+    fn signature(&self) -> nu_protocol::Signature {
+        Signature::build("open")
+            .required("path", SyntaxShape::String, "the file to open")
+            .switch(
+                "raw",
+                "open the file as it is, without converting it based on the file extension",
+                Some('r'),
+            )
+    }
+
+    fn run(
+        &self,
+        context: &EvaluationContext,
+        call: &Call,
+        _input: Value,
+    ) -> Result<nu_protocol::Value, nu_protocol::ShellError> {
+        let path_expr = &call.positional[0];
+        let span = path_expr.span;
+        let path = current_dir(context)?.join(eval_expression(context, path_expr)?.as_string()?);
+
+        let io_error = |err: std::io::Error| ShellError::IOError(err.to_string(), span);
+
+        let mut file = File::open(&path).map_err(io_error)?;
+
+        let converter = match path.extension() {
+            Some(ext) if !call.has_flag("raw") => {
+                let engine_state = context.engine_state.borrow();
+                engine_state.find_decl(format!("from {}", ext.to_string_lossy()).as_bytes())
+            }
+            _ => None,
+        };
+
+        let value = if file.metadata().map_err(io_error)?.len() > STREAM_THRESHOLD {
+            let stream = RawStream::from_reader(file);
+
+            match converter {
+                // Converters only take values, they get the file decoded as it is read
+                Some(_) => Value::Stream {
+                    stream: stream.into_value_stream(span),
+                    span,
+                },
+                None => return Ok(Value::RawStream { stream, span }),
+            }
+        } else {
+            let mut bytes = vec![];
+            file.read_to_end(&mut bytes).map_err(io_error)?;
+
+            match String::from_utf8(bytes) {
+                Ok(val) => Value::String { val, span },
+                Err(err) => Value::Binary {
+                    val: err.into_bytes(),
+                    span,
+                },
+            }
+        };
+
+        match converter {
+            Some(decl_id) => run_converter(context, decl_id, call.head, value),
+            None => Ok(value),
+        }
+    }
+}
+
+/// Pipe the input into a format converter, like `from json`, which may also be defined with `def`
+pub(super) fn run_converter(
+    context: &EvaluationContext,
+    decl_id: DeclId,
+    head: Span,
+    input: Value,
+) -> Result<Value, ShellError> {
+    let mut call = Call::new();
+    call.decl_id = decl_id;
+    call.head = head;
+
+    eval_call(context, &call, input)
+}
@@ -0,0 +1,129 @@
+use std::fs::OpenOptions;
+use std::io::Write;
+
+use nu_engine::{current_dir, eval_expression};
+use nu_protocol::ast::Call;
+use nu_protocol::engine::{Command, EvaluationContext};
+use nu_protocol::{IntoValueStream, ShellError, Signature, Span, SyntaxShape, Value};
+
+use super::open::run_converter;
+
+pub struct Save;
+
+impl Command for Save {
+    fn name(&self) -> &str {
+        "save"
+    }
+
+    fn usage(&self) -> &str {
+        "Save the input to a file."
+    }
+
+    fn signature(&self) -> nu_protocol::Signature {
+        Signature::build("save")
+            .required("path", SyntaxShape::String, "the file to save to")
+            .switch(
+                "append",
+                "append the input to the end of the file",
+                Some('a'),
+            )
+            .switch(
+                "raw",
+                "save the input as it is, without converting it based on the file extension",
+                Some('r'),
+            )
+    }
+
+    fn run(
+        &self,
+        context: &EvaluationContext,
+        call: &Call,
+        input: Value,
+    ) -> Result<nu_protocol::Value, nu_protocol::ShellError> {
+        let path_expr = &call.positional[0];
+        let span = path_expr.span;
+        let path = current_dir(context)?.join(eval_expression(context, path_expr)?.as_string()?);
+
+        let (input, is_text) = peek_text(input);
+
+        // Structured values are serialized with the `to` command that matches the file extension
+        let converter = match path.extension() {
+            Some(ext) if !call.has_flag("raw") && !is_text => {
+                let engine_state = context.engine_state.borrow();
+                engine_state.find_decl(format!("to {}", ext.to_string_lossy()).as_bytes())
+            }
+            _ => None,
+        };
+
+        let input = match converter {
+            Some(decl_id) => run_converter(context, decl_id, call.head, input)?,
+            None => input,
+        };
+
+        let mut file = OpenOptions::new()
+            .write(true)
+            .create(true)
+            .append(call.has_flag("append"))
+            .truncate(!call.has_flag("append"))
+            .open(&path)
+            .map_err(|err| ShellError::IOError(err.to_string(), span))?;
+
+        match input {
+            Value::String { val, .. } => write_bytes(&mut file, val.as_bytes(), span)?,
+            Value::Binary { val, .. } => write_bytes(&mut file, &val, span)?,
+            Value::Stream { stream, .. } => {
+                for value in stream {
+                    match value {
+                        Value::String { val, .. } => write_bytes(&mut file, val.as_bytes(), span)?,
+                        Value::Binary { val, .. } => write_bytes(&mut file, &val, span)?,
+                        Value::Error { error } => return Err(error),
+                        value => return Err(cant_save(&value)),
+                    }
+                }
+            }
+            Value::Nothing { .. } => {}
+            value => return Err(cant_save(&value)),
+        }
+
+        Ok(Value::Nothing { span: call.head })
+    }
+}
+
+/// Find out whether the value is text or bytes that can go to the file as they are. The output of
+/// an external arrives as a stream of those, so streams are judged by their first value.
+fn peek_text(input: Value) -> (Value, bool) {
+    match input {
+        Value::String { .. } | Value::Binary { .. } | Value::Nothing { .. } => (input, true),
+        Value::Stream { mut stream, span } => {
+            let first = stream.next();
+            let is_text = matches!(
+                first,
+                None | Some(Value::String { .. } | Value::Binary { .. })
+            );
+
+            (
+                Value::Stream {
+                    stream: first.into_iter().chain(stream).into_value_stream(),
+                    span,
+                },
+                is_text,
+            )
+        }
+        _ => (input, false),
+    }
+}
+
+fn write_bytes(file: &mut std::fs::File, bytes: &[u8], span: Span) -> Result<(), ShellError> {
+    file.write_all(bytes)
+        .map_err(|err| ShellError::IOError(err.to_string(), span))
+}
+
+fn cant_save(value: &Value) -> ShellError {
+    ShellError::UnsupportedInput(
+        format!(
+            "can't save {} without a converter for the file extension",
+            value.get_type()
+        ),
+        value.span(),
+    )
+}
@@ -0,0 +1,116 @@
+use std::collections::{HashMap, HashSet};
+
+use nu_engine::{eval_block, eval_expression};
+use nu_protocol::ast::Call;
+use nu_protocol::engine::{Command, EvaluationContext};
+use nu_protocol::{ShellError, Signature, SyntaxShape, Value, ValueKey};
+
+use super::sort_by::{column_value, input_rows};
+
+pub struct GroupBy;
+
+impl Command for GroupBy {
+    fn name(&self) -> &str {
+        "group-by"
+    }
+
+    fn usage(&self) -> &str {
+        "Create a record with a list of rows for every value of a column, or of a block."
+    }
+
+    fn signature(&self) -> nu_protocol::Signature {
+        Signature::build("group-by").required(
+            "grouper",
+            SyntaxShape::OneOf(vec![
+                SyntaxShape::Block(Some(vec![SyntaxShape::Any])),
+                SyntaxShape::String,
+            ]),
+            "the column to group by, or a block that returns the group of a row",
+        )
+    }
+
+    fn run(
+        &self,
+        context: &EvaluationContext,
+        call: &Call,
+        input: Value,
+    ) -> Result<nu_protocol::Value, nu_protocol::ShellError> {
+        let grouper = &call.positional[0];
+        let column = match grouper.as_block() {
+            Some(_) => None,
+            None => Some(eval_expression(context, grouper)?.as_string()?),
+        };
+
+        let head = call.head;
+
+        let mut names: Vec<String> = vec![];
+        let mut groups: Vec<Vec<Value>> = vec![];
+        #[allow(clippy::mutable_key_type)]
+        let mut positions: HashMap<ValueKey, usize> = HashMap::new();
+        let mut missing: Vec<Value> = vec![];
+
+        for row in input_rows(input, head)? {
+            let key = match &column {
+                Some(column) => column_value(&row, column),
+                None => {
+                    let block_id = grouper.as_block().expect("internal error: expected block");
+
+                    let engine_state = context.engine_state.borrow();
+                    let block = engine_state.get_block(block_id);
+
+                    let state = context.enter_scope();
+                    if let Some(var) = block.signature.get_positional(0) {
+                        if let Some(var_id) = &var.var_id {
+                            state.add_var(*var_id, row.clone());
+                        }
+                    }
+
+                    eval_block(&state, block, Value::nothing())?
+                }
+            };
+
+            // Rows without a group are put together last
+            match key {
+                Value::Nothing { .. } => missing.push(row),
+                Value::Error { error } => return Err(error),
+                key => {
+                    let key = ValueKey(key);
+                    match positions.get(&key) {
+                        Some(idx) => groups[*idx].push(row),
+                        None => {
+                            names.push(key.0.clone().into_string());
+                            positions.insert(key, groups.len());
+                            groups.push(vec![row]);
+                        }
+                    }
+                }
+            }
+        }
+
+        if !missing.is_empty() {
+            names.push(String::new());
+            groups.push(missing);
+        }
+
+        // The groups are keyed by value, but the record can only have one column for every name,
+        // like `1` and `"1"`, or an empty string and the rows without a group
+        let mut seen = HashSet::new();
+        for name in &names {
+            if !seen.insert(name) {
+                return Err(ShellError::UnsupportedInput(
+                    format!("more than one group would be named '{}'", name),
+                    head,
+                ));
+            }
+        }
+
+        Ok(Value::Record {
+            cols: names,
+            vals: groups
+                .into_iter()
+                .map(|vals| Value::List { vals, span: head })
+                .collect(),
+            span: head,
+        })
+    }
+}
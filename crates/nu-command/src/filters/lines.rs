@@ -1,5 +1,4 @@
-use std::cell::RefCell;
-use std::rc::Rc;
+use std::collections::VecDeque;
 
 use nu_protocol::ast::Call;
 use nu_protocol::engine::{Command, EvaluationContext};
@@ -29,60 +28,16 @@ impl Command for Lines {
         input: Value,
     ) -> Result<nu_protocol::Value, nu_protocol::ShellError> {
         match input {
-            #[allow(clippy::needless_collect)]
-            // Collect is needed because the string may not live long enough for
-            // the Rc structure to continue using it. If split could take ownership
-            // of the split values, then this wouldn't be needed
-            Value::String { val, span } => {
-                let lines = val
-                    .split(SPLIT_CHAR)
-                    .map(|s| s.to_string())
-                    .collect::<Vec<String>>();
-
-                let iter = lines.into_iter().filter_map(move |s| {
-                    if !s.is_empty() {
-                        Some(Value::String { val: s, span })
-                    } else {
-                        None
-                    }
-                });
-
-                Ok(Value::Stream {
-                    stream: ValueStream(Rc::new(RefCell::new(iter))),
-                    span: Span::unknown(),
-                })
-            }
-            Value::Stream { stream, span: _ } => {
-                let iter = stream
-                    .into_iter()
-                    .filter_map(|value| {
-                        if let Value::String { val, span } = value {
-                            let inner = val
-                                .split(SPLIT_CHAR)
-                                .filter_map(|s| {
-                                    if !s.is_empty() {
-                                        Some(Value::String {
-                                            val: s.trim().into(),
-                                            span,
-                                        })
-                                    } else {
-                                        None
-                                    }
-                                })
-                                .collect::<Vec<Value>>();
-
-                            Some(inner)
-                        } else {
-                            None
-                        }
-                    })
-                    .flatten();
-
-                Ok(Value::Stream {
-                    stream: ValueStream(Rc::new(RefCell::new(iter))),
-                    span: Span::unknown(),
-                })
-            }
+            Value::String { .. } | Value::Binary { .. } => Ok(Value::Stream {
+                stream: ValueStream::from_stream(LineSplitter::new(ValueStream::from_stream(
+                    std::iter::once(input),
+                ))),
+                span: Span::unknown(),
+            }),
+            Value::Stream { stream, span: _ } => Ok(Value::Stream {
+                stream: ValueStream::from_stream(LineSplitter::new(stream)),
+                span: Span::unknown(),
+            }),
             val => Err(ShellError::UnsupportedInput(
                 format!("Not supported input: {}", val.as_string()?),
                 call.head,
@@ -90,3 +45,79 @@ impl Command for Lines {
         }
     }
 }
+
+/// Splits the text of a stream into lines. The items of a stream don't line up with lines, so
+/// whatever follows the last newline of an item is carried over to the next one.
+pub struct LineSplitter {
+    input: ValueStream,
+    lines: VecDeque<Value>,
+    partial: String,
+    span: Span,
+    done: bool,
+}
+
+impl LineSplitter {
+    pub fn new(input: ValueStream) -> Self {
+        Self {
+            input,
+            lines: VecDeque::new(),
+            partial: String::new(),
+            span: Span::unknown(),
+            done: false,
+        }
+    }
+
+    fn push_text(&mut self, text: &str) {
+        self.partial.push_str(text);
+
+        while let Some(idx) = self.partial.find(SPLIT_CHAR) {
+            let line: String = self.partial.drain(..=idx).collect();
+            self.push_line(&line[..idx]);
+        }
+    }
+
+    fn push_line(&mut self, line: &str) {
+        let line = line.strip_suffix('\r').unwrap_or(line);
+
+        if !line.is_empty() {
+            self.lines.push_back(Value::String {
+                val: line.into(),
+                span: self.span,
+            });
+        }
+    }
+}
+
+impl Iterator for LineSplitter {
+    type Item = Value;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(line) = self.lines.pop_front() {
+                return Some(line);
+            }
+
+            if self.done {
+                return None;
+            }
+
+            match self.input.next() {
+                Some(Value::String { val, span }) => {
+                    self.span = span;
+                    self.push_text(&val);
+                }
+                Some(Value::Binary { val, span }) => {
+                    self.span = span;
+                    self.push_text(&String::from_utf8_lossy(&val));
+                }
+                Some(_) => continue,
+                None => {
+                    self.done = true;
+
+                    let rest = std::mem::take(&mut self.partial);
+                    self.push_line(&rest);
+                }
+            }
+        }
+    }
+}
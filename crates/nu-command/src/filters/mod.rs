@@ -1,11 +1,19 @@
 mod each;
 mod for_;
+mod group_by;
 mod length;
 mod lines;
+mod sort_by;
+mod uniq;
 mod where_;
 
 pub use each::Each;
 pub use for_::For;
+pub use group_by::GroupBy;
 pub use length::Length;
 pub use lines::Lines;
+pub use sort_by::SortBy;
+pub use uniq::Uniq;
 pub use where_::Where;
+
+pub(crate) use lines::LineSplitter;
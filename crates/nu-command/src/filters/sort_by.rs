@@ -0,0 +1,133 @@
+use std::cmp::Ordering;
+
+use nu_engine::eval_expression;
+use nu_protocol::ast::Call;
+use nu_protocol::engine::{Command, EvaluationContext};
+use nu_protocol::{ShellError, Signature, Span, SyntaxShape, Value};
+
+pub struct SortBy;
+
+impl Command for SortBy {
+    fn name(&self) -> &str {
+        "sort-by"
+    }
+
+    fn usage(&self) -> &str {
+        "Sort by the given columns, or sort the values themselves if no columns are given."
+    }
+
+    fn signature(&self) -> nu_protocol::Signature {
+        Signature::build("sort-by")
+            .rest("columns", SyntaxShape::String, "the columns to sort by")
+            .switch("reverse", "sort in descending order", Some('r'))
+            .switch(
+                "insensitive",
+                "sort strings without regard to upper and lower case",
+                Some('i'),
+            )
+    }
+
+    fn run(
+        &self,
+        context: &EvaluationContext,
+        call: &Call,
+        input: Value,
+    ) -> Result<nu_protocol::Value, nu_protocol::ShellError> {
+        let columns = call
+            .positional
+            .iter()
+            .map(|expr| eval_expression(context, expr)?.as_string())
+            .collect::<Result<Vec<String>, ShellError>>()?;
+        let reverse = call.has_flag("reverse");
+        let insensitive = call.has_flag("insensitive");
+
+        let mut rows: Vec<(Vec<Value>, Value)> = input_rows(input, call.head)?
+            .into_iter()
+            .map(|row| {
+                let key = if columns.is_empty() {
+                    vec![row.clone()]
+                } else {
+                    columns
+                        .iter()
+                        .map(|column| column_value(&row, column))
+                        .collect()
+                };
+
+                let key = key
+                    .into_iter()
+                    .map(|val| match val {
+                        Value::String { val, span } if insensitive => Value::String {
+                            val: val.to_lowercase(),
+                            span,
+                        },
+                        val => val,
+                    })
+                    .collect();
+
+                (key, row)
+            })
+            .collect();
+
+        rows.sort_by(|(lhs, _), (rhs, _)| compare_keys(lhs, rhs, reverse));
+
+        Ok(Value::List {
+            vals: rows.into_iter().map(|(_, row)| row).collect(),
+            span: call.head,
+        })
+    }
+}
+
+// Missing values come last, whichever way the rest is sorted
+fn compare_keys(lhs: &[Value], rhs: &[Value], reverse: bool) -> Ordering {
+    for (lhs, rhs) in lhs.iter().zip(rhs) {
+        let ordering = match (lhs, rhs) {
+            (Value::Nothing { .. }, Value::Nothing { .. }) => Ordering::Equal,
+            (Value::Nothing { .. }, _) => Ordering::Greater,
+            (_, Value::Nothing { .. }) => Ordering::Less,
+            (lhs, rhs) if reverse => rhs.compare(lhs),
+            (lhs, rhs) => lhs.compare(rhs),
+        };
+
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+
+    Ordering::Equal
+}
+
+/// The rows of a list or stream, or a record as a table with one row
+pub(super) fn input_rows(input: Value, head: Span) -> Result<Vec<Value>, ShellError> {
+    let rows = match input {
+        Value::List { vals, .. } => vals,
+        Value::Stream { stream, .. } => stream.collect(),
+        record @ Value::Record { .. } => vec![record],
+        Value::Error { error } => return Err(error),
+        value => {
+            return Err(ShellError::UnsupportedInput(
+                format!("expected a list or a table, found {}", value.get_type()),
+                head,
+            ))
+        }
+    };
+
+    for row in &rows {
+        if let Value::Error { error } = row {
+            return Err(error.clone());
+        }
+    }
+
+    Ok(rows)
+}
+
+/// The value of a column, which is nothing for rows that don't have it
+pub(super) fn column_value(row: &Value, column: &str) -> Value {
+    match row {
+        Value::Record { cols, vals, .. } => cols
+            .iter()
+            .position(|col| col == column)
+            .map(|idx| vals[idx].clone()),
+        _ => None,
+    }
+    .unwrap_or(Value::Nothing { span: row.span() })
+}
@@ -0,0 +1,74 @@
+use std::collections::HashMap;
+
+use nu_protocol::ast::Call;
+use nu_protocol::engine::{Command, EvaluationContext};
+use nu_protocol::{Signature, Value, ValueKey};
+
+use super::sort_by::input_rows;
+
+pub struct Uniq;
+
+impl Command for Uniq {
+    fn name(&self) -> &str {
+        "uniq"
+    }
+
+    fn usage(&self) -> &str {
+        "Return the distinct values, in the order they first appear."
+    }
+
+    fn signature(&self) -> nu_protocol::Signature {
+        Signature::build("uniq").switch(
+            "count",
+            "return every value with the number of times it appears",
+            Some('c'),
+        )
+    }
+
+    fn run(
+        &self,
+        _context: &EvaluationContext,
+        call: &Call,
+        input: Value,
+    ) -> Result<nu_protocol::Value, nu_protocol::ShellError> {
+        let head = call.head;
+
+        let mut uniques: Vec<(Value, i64)> = vec![];
+        // Streams are the only values that can change, and they aren't part of the hash
+        #[allow(clippy::mutable_key_type)]
+        let mut positions: HashMap<ValueKey, usize> = HashMap::new();
+
+        for row in input_rows(input, head)? {
+            let key = ValueKey(row);
+            match positions.get(&key) {
+                Some(idx) => uniques[*idx].1 += 1,
+                None => {
+                    let row = key.0.clone();
+                    positions.insert(key, uniques.len());
+                    uniques.push((row, 1));
+                }
+            }
+        }
+
+        let vals = if call.has_flag("count") {
+            uniques
+                .into_iter()
+                .map(|(value, count)| Value::Record {
+                    cols: vec!["value".into(), "count".into()],
+                    vals: vec![
+                        value,
+                        Value::Int {
+                            val: count,
+                            span: head,
+                        },
+                    ],
+                    span: head,
+                })
+                .collect()
+        } else {
+            uniques.into_iter().map(|(value, _)| value).collect()
+        };
+
+        Ok(Value::List { vals, span: head })
+    }
+}
@@ -0,0 +1,43 @@
+use nu_protocol::ast::Call;
+use nu_protocol::engine::{Command, EvaluationContext};
+use nu_protocol::{Signature, SyntaxShape, Value};
+
+use super::delimited::{from_delimited, separator_flag};
+
+pub struct FromCsv;
+
+impl Command for FromCsv {
+    fn name(&self) -> &str {
+        "from csv"
+    }
+
+    fn usage(&self) -> &str {
+        "Parse text as .csv and create a table."
+    }
+
+    fn signature(&self) -> nu_protocol::Signature {
+        Signature::build("from csv")
+            .switch(
+                "noheaders",
+                "don't treat the first row as column names",
+                Some('n'),
+            )
+            .named(
+                "separator",
+                SyntaxShape::String,
+                "a character to separate columns, defaults to a comma",
+                Some('s'),
+            )
+    }
+
+    fn run(
+        &self,
+        context: &EvaluationContext,
+        call: &Call,
+        input: Value,
+    ) -> Result<nu_protocol::Value, nu_protocol::ShellError> {
+        let separator = separator_flag(context, call, b',')?;
+
+        from_delimited(input, separator, call.has_flag("noheaders"), call.head)
+    }
+}
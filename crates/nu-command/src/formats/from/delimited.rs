@@ -0,0 +1,195 @@
+use nu_engine::eval_expression;
+use nu_protocol::ast::Call;
+use nu_protocol::engine::EvaluationContext;
+use nu_protocol::{IntoValueStream, ShellError, Span, Value};
+
+use super::input::InputReader;
+
+/// The number of records the column types are inferred from
+const INFERENCE_ROWS: usize = 1000;
+
+/// Parse delimiter separated values into a stream of records. The input is read as the records
+/// are consumed, so a large file never has to be in memory at once. Only the first records are
+/// read ahead, to find out which columns hold numbers.
+pub fn from_delimited(
+    input: Value,
+    separator: u8,
+    noheaders: bool,
+    head: Span,
+) -> Result<Value, ShellError> {
+    let span = match input.span() {
+        span if span == Span::unknown() => head,
+        span => span,
+    };
+
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(separator)
+        .has_headers(!noheaders)
+        .from_reader(InputReader::new(input, head)?);
+
+    let headers = if noheaders {
+        None
+    } else {
+        let headers = reader
+            .headers()
+            .map_err(|err| ShellError::CantParse("csv".into(), err.to_string(), span))?;
+
+        Some(headers.iter().map(String::from).collect::<Vec<_>>())
+    };
+
+    let mut records = reader.into_records();
+    let sample: Vec<_> = records.by_ref().take(INFERENCE_ROWS).collect();
+    let types = column_types(sample.iter().flatten().map(|record| record.iter()));
+
+    let records = sample
+        .into_iter()
+        .chain(records)
+        .map(move |record| match record {
+            Ok(record) => {
+                let cols = match &headers {
+                    Some(headers) => headers.clone(),
+                    None => (1..=record.len())
+                        .map(|idx| format!("column{}", idx))
+                        .collect(),
+                };
+
+                Value::Record {
+                    cols,
+                    vals: record
+                        .iter()
+                        .enumerate()
+                        .map(|(idx, field)| {
+                            let column_type = types.get(idx).copied().unwrap_or(ColumnType::String);
+                            convert_field(field, column_type, head)
+                        })
+                        .collect(),
+                    span: head,
+                }
+            }
+            Err(err) => Value::Error {
+                error: ShellError::CantParse("csv".into(), err.to_string(), span),
+            },
+        });
+
+    Ok(Value::Stream {
+        stream: records.into_value_stream(),
+        span: head,
+    })
+}
+
+/// The separator given with `--separator`, which has to be a single character
+pub fn separator_flag(
+    context: &EvaluationContext,
+    call: &Call,
+    default: u8,
+) -> Result<u8, ShellError> {
+    match call.get_flag_expr("separator") {
+        Some(expr) => {
+            let separator = eval_expression(context, expr)?.as_string()?;
+
+            match separator.as_bytes() {
+                [separator] => Ok(*separator),
+                _ => Err(ShellError::UnsupportedInput(
+                    "the separator has to be a single character".into(),
+                    expr.span,
+                )),
+            }
+        }
+        None => Ok(default),
+    }
+}
+
+/// What the fields of a column hold
+#[derive(Clone, Copy, PartialEq)]
+enum ColumnType {
+    // An empty field fits in a column of any type
+    Empty,
+    Int,
+    Float,
+    String,
+}
+
+impl ColumnType {
+    // Ints fit in a float column, anything else makes it a column of strings
+    fn merge(self, other: ColumnType) -> ColumnType {
+        match (self, other) {
+            (ColumnType::Empty, other) | (other, ColumnType::Empty) => other,
+            (ColumnType::Int, ColumnType::Int) => ColumnType::Int,
+            (ColumnType::String, _) | (_, ColumnType::String) => ColumnType::String,
+            _ => ColumnType::Float,
+        }
+    }
+}
+
+// Words like `inf` and `nan` would parse as floats too, so a float needs at least one digit. A
+// number with leading zeros, like `007`, is most likely a code and not a number.
+fn field_type(field: &str) -> ColumnType {
+    let digits = field.trim_start_matches(['-', '+']);
+    let leading_zero = digits.len() > 1
+        && digits.starts_with('0')
+        && digits[1..].starts_with(|c: char| c.is_ascii_digit());
+
+    if field.is_empty() {
+        ColumnType::Empty
+    } else if leading_zero {
+        ColumnType::String
+    } else if field.parse::<i64>().is_ok() {
+        ColumnType::Int
+    } else if field.parse::<f64>().is_ok() && field.chars().any(|c| c.is_ascii_digit()) {
+        ColumnType::Float
+    } else {
+        ColumnType::String
+    }
+}
+
+/// The type of every column: a column only holds numbers if all of its fields are numbers
+fn column_types<'a>(rows: impl Iterator<Item = impl Iterator<Item = &'a str>>) -> Vec<ColumnType> {
+    let mut types: Vec<ColumnType> = vec![];
+
+    for row in rows {
+        for (idx, field) in row.enumerate() {
+            let field_type = field_type(field);
+
+            match types.get_mut(idx) {
+                Some(column_type) => *column_type = column_type.merge(field_type),
+                None => types.push(field_type),
+            }
+        }
+    }
+
+    types
+}
+
+// Fields after the rows the types were inferred from can still be something else, those stay
+// strings. Empty fields are missing values.
+fn convert_field(field: &str, column_type: ColumnType, span: Span) -> Value {
+    if field.is_empty() {
+        return Value::Nothing { span };
+    }
+
+    match column_type {
+        ColumnType::Int => match field.parse::<i64>() {
+            Ok(val) if field_type(field) == ColumnType::Int => Value::Int { val, span },
+            _ => Value::String {
+                val: field.into(),
+                span,
+            },
+        },
+        ColumnType::Float => match field.parse::<f64>() {
+            Ok(val) if field_type(field) != ColumnType::String => Value::Float { val, span },
+            _ => Value::String {
+                val: field.into(),
+                span,
+            },
+        },
+        ColumnType::String | ColumnType::Empty => Value::String {
+            val: field.into(),
+            span,
+        },
+    }
+}
+
+/// A number if the field is one, nothing if it's empty, and a string otherwise
+pub fn infer_value(field: &str, span: Span) -> Value {
+    convert_field(field, field_type(field), span)
+}
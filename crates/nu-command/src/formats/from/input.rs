@@ -0,0 +1,171 @@
+use std::io::{ErrorKind, Read};
+
+use nu_protocol::engine::EvaluationContext;
+use nu_protocol::{IntoValueStream, ShellError, Span, Value, ValueStream};
+
+/// The text a `from` command parses, along with where it came from
+pub struct InputText {
+    pub text: String,
+    pub span: Span,
+    // Where the text starts in the source, when it is a literal that errors can point into
+    start: Option<usize>,
+}
+
+impl InputText {
+    /// Collect the input of a `from` command into a single string
+    pub fn collect(
+        context: &EvaluationContext,
+        input: Value,
+        head: Span,
+    ) -> Result<InputText, ShellError> {
+        let (text, span) = match input {
+            Value::String { val, span } => (val, span),
+            Value::Binary { val, span } => (String::from_utf8_lossy(&val).into_owned(), span),
+            Value::Stream { stream, span } => {
+                let mut text = String::new();
+
+                for value in stream {
+                    match value {
+                        Value::String { val, .. } => text.push_str(&val),
+                        Value::Binary { val, .. } => text.push_str(&String::from_utf8_lossy(&val)),
+                        Value::Error { error } => return Err(error),
+                        value => return Err(unsupported_input(&value, head)),
+                    }
+                }
+
+                (text, span)
+            }
+            value => return Err(unsupported_input(&value, head)),
+        };
+
+        let start = source_start(context, &text, span);
+
+        Ok(InputText { text, span, start })
+    }
+
+    /// The span of the character at the given offset of the text, or the span of the whole
+    /// input if the text can't be found in the source
+    pub fn span_at(&self, offset: usize) -> Span {
+        match self.start {
+            Some(start) => {
+                let offset = offset.min(self.text.len().saturating_sub(1));
+                let len = self
+                    .text
+                    .get(offset..)
+                    .and_then(|rest| rest.chars().next())
+                    .map_or(1, char::len_utf8);
+
+                Span {
+                    start: start + offset,
+                    end: start + offset + len,
+                }
+            }
+            None => self.span,
+        }
+    }
+
+    /// The byte offset of the character at the given index of a part of the text starting at
+    /// `offset`
+    pub fn char_offset(&self, offset: usize, index: usize) -> usize {
+        self.text[offset..]
+            .char_indices()
+            .nth(index)
+            .map_or(self.text.len(), |(char_offset, _)| offset + char_offset)
+    }
+
+    /// The offset of a 1-based line and column in a part of the text starting at `offset`
+    pub fn offset_of(&self, offset: usize, line: usize, column: usize) -> usize {
+        let line_start: usize = self.text[offset..]
+            .split_inclusive('\n')
+            .take(line.saturating_sub(1))
+            .map(|line| line.len())
+            .sum();
+
+        offset + line_start + column.saturating_sub(1)
+    }
+}
+
+/// Reads the input of a `from` command as it streams in, for formats that can be parsed bit by bit
+pub struct InputReader {
+    values: ValueStream,
+    buf: Vec<u8>,
+    pos: usize,
+}
+
+impl InputReader {
+    pub fn new(input: Value, head: Span) -> Result<InputReader, ShellError> {
+        let values = match input {
+            Value::Stream { stream, .. } => stream,
+            Value::String { .. } | Value::Binary { .. } => {
+                std::iter::once(input).into_value_stream()
+            }
+            value => return Err(unsupported_input(&value, head)),
+        };
+
+        Ok(InputReader {
+            values,
+            buf: vec![],
+            pos: 0,
+        })
+    }
+}
+
+impl Read for InputReader {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        while self.pos == self.buf.len() {
+            self.buf = match self.values.next() {
+                Some(Value::String { val, .. }) => val.into_bytes(),
+                Some(Value::Binary { val, .. }) => val,
+                Some(Value::Error { error }) => {
+                    return Err(std::io::Error::new(
+                        ErrorKind::InvalidData,
+                        error.to_string(),
+                    ))
+                }
+                Some(value) => {
+                    return Err(std::io::Error::new(
+                        ErrorKind::InvalidData,
+                        format!("expected text, found {}", value.get_type()),
+                    ))
+                }
+                None => return Ok(0),
+            };
+            self.pos = 0;
+        }
+
+        let len = buf.len().min(self.buf.len() - self.pos);
+        buf[..len].copy_from_slice(&self.buf[self.pos..self.pos + len]);
+        self.pos += len;
+
+        Ok(len)
+    }
+}
+
+// Where the text starts in the source, if the span covers it as written or in quotes
+fn source_start(context: &EvaluationContext, text: &str, span: Span) -> Option<usize> {
+    let engine_state = context.engine_state.borrow();
+    if span.start >= span.end || span.end > engine_state.next_span_start() {
+        return None;
+    }
+
+    let source = engine_state.get_span_contents(&span);
+    if source == text.as_bytes() {
+        return Some(span.start);
+    }
+
+    match (source.first(), source.last()) {
+        (Some(open), Some(close))
+            if source.len() == text.len() + 2
+                && open == close
+                && matches!(open, b'\'' | b'"' | b'`')
+                && &source[1..source.len() - 1] == text.as_bytes() =>
+        {
+            Some(span.start + 1)
+        }
+        _ => None,
+    }
+}
+
+fn unsupported_input(value: &Value, head: Span) -> ShellError {
+    ShellError::UnsupportedInput(format!("expected text, found {}", value.get_type()), head)
+}
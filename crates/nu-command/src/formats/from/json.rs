@@ -0,0 +1,118 @@
+use nu_protocol::ast::Call;
+use nu_protocol::engine::{Command, EvaluationContext};
+use nu_protocol::{IntoValueStream, ShellError, Signature, Span, Value};
+
+use super::input::InputText;
+use crate::filters::LineSplitter;
+
+pub struct FromJson;
+
+impl Command for FromJson {
+    fn name(&self) -> &str {
+        "from json"
+    }
+
+    fn usage(&self) -> &str {
+        "Convert from json to structured data."
+    }
+
+    fn signature(&self) -> nu_protocol::Signature {
+        Signature::build("from json").switch(
+            "objects",
+            "read newline-delimited json, with a value on every line",
+            Some('o'),
+        )
+    }
+
+    fn run(
+        &self,
+        context: &EvaluationContext,
+        call: &Call,
+        input: Value,
+    ) -> Result<nu_protocol::Value, nu_protocol::ShellError> {
+        let head = call.head;
+
+        if call.has_flag("objects") {
+            let stream = match input {
+                Value::Stream { stream, .. } => stream,
+                Value::String { .. } | Value::Binary { .. } => {
+                    std::iter::once(input).into_value_stream()
+                }
+                value => {
+                    return Err(ShellError::UnsupportedInput(
+                        format!("expected text, found {}", value.get_type()),
+                        head,
+                    ))
+                }
+            };
+
+            // Lines are split off the input as it comes in, so endless streams work too
+            let vals = LineSplitter::new(stream).filter_map(move |line| match line {
+                Value::String { val, span } if !val.trim().is_empty() => {
+                    Some(match parse_json(&val, head, |_, _| span) {
+                        Ok(value) => value,
+                        Err(error) => Value::Error { error },
+                    })
+                }
+                _ => None,
+            });
+
+            Ok(Value::Stream {
+                stream: vals.into_value_stream(),
+                span: head,
+            })
+        } else {
+            let input = InputText::collect(context, input, head)?;
+
+            parse_json(&input.text, head, |line, column| {
+                input.span_at(input.offset_of(0, line, column))
+            })
+        }
+    }
+}
+
+// `error_span` turns the line and column of an error into the span it points at
+fn parse_json(
+    text: &str,
+    span: Span,
+    error_span: impl FnOnce(usize, usize) -> Span,
+) -> Result<Value, ShellError> {
+    match serde_json::from_str(text) {
+        Ok(value) => Ok(convert_json(value, span)),
+        Err(err) => Err(ShellError::CantParse(
+            "json".into(),
+            err.to_string(),
+            error_span(err.line(), err.column()),
+        )),
+    }
+}
+
+fn convert_json(value: serde_json::Value, span: Span) -> Value {
+    match value {
+        serde_json::Value::Null => Value::Nothing { span },
+        serde_json::Value::Bool(val) => Value::Bool { val, span },
+        serde_json::Value::Number(number) => match number.as_i64() {
+            Some(val) => Value::Int { val, span },
+            None => Value::Float {
+                val: number.as_f64().unwrap_or(f64::NAN),
+                span,
+            },
+        },
+        serde_json::Value::String(val) => Value::String { val, span },
+        serde_json::Value::Array(vals) => Value::List {
+            vals: vals
+                .into_iter()
+                .map(|val| convert_json(val, span))
+                .collect(),
+            span,
+        },
+        serde_json::Value::Object(object) => {
+            let (cols, vals) = object
+                .into_iter()
+                .map(|(col, val)| (col, convert_json(val, span)))
+                .unzip();
+
+            Value::Record { cols, vals, span }
+        }
+    }
+}
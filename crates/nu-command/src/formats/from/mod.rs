@@ -0,0 +1,24 @@
+mod command;
+mod csv;
+mod delimited;
+mod input;
+mod json;
+mod nuon;
+mod ssv;
+mod toml;
+mod tsv;
+mod xml;
+mod yaml;
+
+pub use self::csv::FromCsv;
+pub use self::toml::FromToml;
+pub use command::From;
+pub use json::FromJson;
+pub use nuon::FromNuon;
+pub use ssv::FromSsv;
+pub use tsv::FromTsv;
+pub use xml::FromXml;
+pub use yaml::FromYaml;
+
+pub(crate) use delimited::{infer_value, separator_flag};
+pub(crate) use ssv::{aligned_fields, header_columns, merge_spaced_columns, text_lines, to_record};
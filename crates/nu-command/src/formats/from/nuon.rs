@@ -0,0 +1,222 @@
+use nu_parser::parse;
+use nu_protocol::ast::{Call, Expr, Expression, Operator, Statement};
+use nu_protocol::engine::{Command, EvaluationContext, StateWorkingSet};
+use nu_protocol::{BlockId, Range, ShellError, Signature, Span, Value};
+
+use super::input::InputText;
+
+pub struct FromNuon;
+
+impl Command for FromNuon {
+    fn name(&self) -> &str {
+        "from nuon"
+    }
+
+    fn usage(&self) -> &str {
+        "Convert from nuon to structured data."
+    }
+
+    fn signature(&self) -> nu_protocol::Signature {
+        Signature::build("from nuon")
+    }
+
+    fn run(
+        &self,
+        context: &EvaluationContext,
+        call: &Call,
+        input: Value,
+    ) -> Result<nu_protocol::Value, nu_protocol::ShellError> {
+        let input = InputText::collect(context, input, call.head)?;
+
+        let engine_state = context.engine_state.borrow();
+        let mut working_set = StateWorkingSet::new(&engine_state);
+
+        let start = working_set.next_span_start();
+
+        // The text is only parsed, nothing in it ever gets evaluated. Anything that isn't a
+        // literal, like a command call or a variable, is an error.
+        let (block, err) = parse(&mut working_set, Some("nuon"), input.text.as_bytes(), false);
+
+        let parser = NuonParser {
+            working_set: &working_set,
+            start,
+            input: &input,
+            head: call.head,
+        };
+
+        if let Some(err) = err {
+            let offset = miette::Diagnostic::labels(&err)
+                .and_then(|mut labels| labels.next())
+                .map(|label| label.offset());
+
+            return Err(ShellError::CantParse(
+                "nuon".into(),
+                err.to_string(),
+                match offset {
+                    Some(offset) => parser.span_of(Span {
+                        start: offset,
+                        end: offset,
+                    }),
+                    None => input.span,
+                },
+            ));
+        }
+
+        let mut exprs = vec![];
+        for stmt in &block.stmts {
+            match stmt {
+                Statement::Pipeline(pipeline) => exprs.extend(&pipeline.expressions),
+                Statement::Declaration(_) => {
+                    return Err(ShellError::CantParse(
+                        "nuon".into(),
+                        "declarations are not allowed".into(),
+                        input.span,
+                    ))
+                }
+            }
+        }
+
+        match exprs.as_slice() {
+            [] => Ok(Value::Nothing { span: call.head }),
+            [expr] => parser.convert(expr),
+            [_, expr, ..] => Err(parser.error("expected a single value", expr.span)),
+        }
+    }
+}
+
+struct NuonParser<'a> {
+    working_set: &'a StateWorkingSet<'a>,
+    // Where the text starts in the spans of the working set
+    start: usize,
+    input: &'a InputText,
+    head: Span,
+}
+
+impl<'a> NuonParser<'a> {
+    fn convert(&self, expr: &Expression) -> Result<Value, ShellError> {
+        let span = self.head;
+
+        Ok(match &expr.expr {
+            Expr::Bool(val) => Value::Bool { val: *val, span },
+            Expr::Int(val) => Value::Int { val: *val, span },
+            Expr::Float(val) => Value::Float { val: *val, span },
+            Expr::String(val) => Value::String {
+                val: val.clone(),
+                span,
+            },
+            Expr::Binary(val) => Value::Binary {
+                val: val.clone(),
+                span,
+            },
+            Expr::Nothing => Value::Nothing { span },
+            Expr::List(exprs) => Value::List {
+                vals: exprs
+                    .iter()
+                    .map(|expr| self.convert(expr))
+                    .collect::<Result<_, _>>()?,
+                span,
+            },
+            Expr::Table(headers, rows) => {
+                let cols = headers
+                    .iter()
+                    .map(|header| self.convert_column(header))
+                    .collect::<Result<Vec<_>, _>>()?;
+
+                let mut vals = vec![];
+                for row in rows {
+                    vals.push(Value::Record {
+                        cols: cols.clone(),
+                        vals: row
+                            .iter()
+                            .map(|expr| self.convert(expr))
+                            .collect::<Result<_, _>>()?,
+                        span,
+                    });
+                }
+
+                Value::List { vals, span }
+            }
+            Expr::Record(fields) => {
+                let mut cols = vec![];
+                let mut vals = vec![];
+                for (col, val) in fields {
+                    cols.push(self.convert_column(col)?);
+                    vals.push(self.convert(val)?);
+                }
+
+                Value::Record { cols, vals, span }
+            }
+            Expr::Subexpression(block_id) => Value::String {
+                val: self.convert_concatenation(*block_id, expr.span)?,
+                span,
+            },
+            // `$true` and `$nothing` are parsed like variables, as cell paths without members
+            Expr::FullCellPath(cell_path) if cell_path.tail.is_empty() => {
+                self.convert(&cell_path.head)?
+            }
+            Expr::Range(from, next, to, operator) => {
+                let bound = |expr: &Option<Box<Expression>>| match expr {
+                    Some(expr) => self.convert(expr),
+                    None => Ok(Value::Nothing { span }),
+                };
+
+                Value::Range {
+                    val: Box::new(Range::new(
+                        span,
+                        bound(from)?,
+                        bound(next)?,
+                        bound(to)?,
+                        operator,
+                    )?),
+                    span,
+                }
+            }
+            _ => return Err(self.literals_only(expr.span)),
+        })
+    }
+
+    fn convert_column(&self, expr: &Expression) -> Result<String, ShellError> {
+        match self.convert(expr)? {
+            Value::String { val, .. } => Ok(val),
+            _ => Err(self.error("expected a column name", expr.span)),
+        }
+    }
+
+    // A string with both quote characters is written in parts, as `("it's " + '"nuon"')`
+    fn convert_concatenation(&self, block_id: BlockId, span: Span) -> Result<String, ShellError> {
+        let block = self.working_set.get_block(block_id);
+
+        match block.stmts.as_slice() {
+            [Statement::Pipeline(pipeline)] if pipeline.expressions.len() == 1 => {
+                self.convert_string_parts(&pipeline.expressions[0])
+            }
+            _ => Err(self.literals_only(span)),
+        }
+    }
+
+    fn convert_string_parts(&self, expr: &Expression) -> Result<String, ShellError> {
+        match &expr.expr {
+            Expr::String(val) => Ok(val.clone()),
+            Expr::BinaryOp(lhs, op, rhs) if matches!(op.expr, Expr::Operator(Operator::Plus)) => {
+                Ok(self.convert_string_parts(lhs)? + &self.convert_string_parts(rhs)?)
+            }
+            _ => Err(self.literals_only(expr.span)),
+        }
+    }
+
+    fn literals_only(&self, span: Span) -> ShellError {
+        self.error(
+            "only literal values are allowed, not commands or variables",
+            span,
+        )
+    }
+
+    fn error(&self, msg: &str, span: Span) -> ShellError {
+        ShellError::CantParse("nuon".into(), msg.into(), self.span_of(span))
+    }
+
+    // Spans of the parsed text are offsets into the working set, the errors point into the input
+    fn span_of(&self, span: Span) -> Span {
+        self.input.span_at(span.start.saturating_sub(self.start))
+    }
+}
@@ -0,0 +1,286 @@
+use nu_engine::eval_expression;
+use nu_protocol::ast::Call;
+use nu_protocol::engine::{Command, EvaluationContext};
+use nu_protocol::{IntoValueStream, ShellError, Signature, Span, SyntaxShape, Value, ValueStream};
+
+use super::infer_value;
+use crate::filters::LineSplitter;
+
+/// The number of spaces that separate columns, if `--minimum-spaces` isn't given
+const DEFAULT_MINIMUM_SPACES: usize = 2;
+
+pub struct FromSsv;
+
+impl Command for FromSsv {
+    fn name(&self) -> &str {
+        "from ssv"
+    }
+
+    fn usage(&self) -> &str {
+        "Parse text as space-separated values and create a table."
+    }
+
+    fn signature(&self) -> nu_protocol::Signature {
+        Signature::build("from ssv")
+            .switch(
+                "noheaders",
+                "don't treat the first line as column names",
+                Some('n'),
+            )
+            .switch(
+                "aligned-columns",
+                "find the columns from where the column names are in the first line",
+                Some('a'),
+            )
+            .named(
+                "minimum-spaces",
+                SyntaxShape::Int,
+                "the number of spaces that separate columns (2 by default)",
+                Some('m'),
+            )
+    }
+
+    fn run(
+        &self,
+        context: &EvaluationContext,
+        call: &Call,
+        input: Value,
+    ) -> Result<nu_protocol::Value, nu_protocol::ShellError> {
+        let noheaders = call.has_flag("noheaders");
+        let aligned = call.has_flag("aligned-columns");
+        let min_spaces = match call.get_flag_expr("minimum-spaces") {
+            Some(expr) => match eval_expression(context, expr)? {
+                Value::Int { val, .. } if val > 0 => val as usize,
+                val => return Err(ShellError::CantConvert("minimum spaces".into(), val.span())),
+            },
+            None => DEFAULT_MINIMUM_SPACES,
+        };
+
+        let head = call.head;
+        let mut lines = text_lines(input, head)?.peekable();
+
+        let header = match lines.peek() {
+            Some(line) => line.as_string()?,
+            None => {
+                return Ok(Value::List {
+                    vals: vec![],
+                    span: head,
+                })
+            }
+        };
+
+        // Without headers the first line is still used to find the columns, but it is data too
+        let columns = header_columns(&header, min_spaces);
+        let headers: Vec<String> = if noheaders {
+            vec![]
+        } else {
+            lines.next();
+            columns.iter().map(|column| column.name.clone()).collect()
+        };
+
+        let records = lines.map(move |line| {
+            let line = match line.as_string() {
+                Ok(line) => line,
+                Err(error) => return Value::Error { error },
+            };
+
+            let fields = if aligned {
+                aligned_fields(&line, &columns)
+            } else {
+                split_fields(&line.chars().collect::<Vec<_>>(), min_spaces)
+                    .into_iter()
+                    .map(|field| field.text)
+                    .collect()
+            };
+
+            to_record(&headers, fields, head)
+        });
+
+        Ok(Value::Stream {
+            stream: records.into_value_stream(),
+            span: head,
+        })
+    }
+}
+
+/// A column of aligned text, found from where its name is in the header line
+pub struct Column {
+    pub name: String,
+    start: usize,
+    end: usize,
+}
+
+struct Field {
+    text: String,
+    start: usize,
+    end: usize,
+}
+
+/// The columns of the header line, which are separated by at least `min_spaces` spaces
+pub fn header_columns(line: &str, min_spaces: usize) -> Vec<Column> {
+    split_fields(&line.chars().collect::<Vec<_>>(), min_spaces)
+        .into_iter()
+        .map(|field| Column {
+            name: field.text,
+            start: field.start,
+            end: field.end,
+        })
+        .collect()
+}
+
+/// Merge column names that are one name with a space in it, like `CONTAINER ID` or `Mounted on`.
+/// A name only one space after the one before it joins that one when every word of the lines
+/// that reaches under it started before it, so the data has no gap between the two names.
+pub fn merge_spaced_columns(columns: Vec<Column>, lines: &[String]) -> Vec<Column> {
+    let words: Vec<Field> = lines
+        .iter()
+        .flat_map(|line| split_fields(&line.chars().collect::<Vec<_>>(), 1))
+        .collect();
+
+    let mut merged: Vec<Column> = vec![];
+    for column in columns {
+        if let Some(last) = merged.last_mut() {
+            let spanned = last.end + 1 == column.start
+                && words
+                    .iter()
+                    .filter(|word| word.start < column.end && word.end > column.start)
+                    .all(|word| word.start < column.start);
+
+            if spanned {
+                last.name.push(' ');
+                last.name.push_str(&column.name);
+                last.end = column.end;
+                continue;
+            }
+        }
+
+        merged.push(column);
+    }
+
+    merged
+}
+
+/// Split a line into the columns found in the header line. Every word goes to the column whose
+/// name it is under, so right aligned numbers that stick out to the left of their column name
+/// still end up in the right place. Words that aren't under any column name belong to the column
+/// before them, which lets the last column have spaces in it.
+pub fn aligned_fields(line: &str, columns: &[Column]) -> Vec<String> {
+    if columns.is_empty() {
+        return vec![];
+    }
+
+    let chars: Vec<char> = line.chars().collect();
+    let mut ranges: Vec<Option<(usize, usize)>> = vec![None; columns.len()];
+    let mut current = 0;
+
+    for word in split_fields(&chars, 1) {
+        let under = columns
+            .iter()
+            .position(|column| word.start < column.end && word.end > column.start);
+        let before = || {
+            columns
+                .iter()
+                .rposition(|column| column.start <= word.start)
+                .unwrap_or(0)
+        };
+
+        // Words never go back to an earlier column than the word before them
+        current = under.unwrap_or_else(before).max(current);
+
+        ranges[current] = Some(match ranges[current] {
+            Some((start, _)) => (start, word.end),
+            None => (word.start, word.end),
+        });
+    }
+
+    ranges
+        .into_iter()
+        .map(|range| match range {
+            Some((start, end)) => chars[start..end].iter().collect(),
+            None => String::new(),
+        })
+        .collect()
+}
+
+// Fields are separated by at least `min_spaces` whitespace characters, or by a tab. The positions
+// are in characters, so text with multi-byte characters still lines up.
+fn split_fields(chars: &[char], min_spaces: usize) -> Vec<Field> {
+    let mut ranges = vec![];
+    let mut field: Option<(usize, usize)> = None;
+    let mut spaces = 0;
+    let mut tab = false;
+
+    for (idx, c) in chars.iter().enumerate() {
+        if c.is_whitespace() {
+            spaces += 1;
+            tab |= *c == '\t';
+            continue;
+        }
+
+        field = match field {
+            Some((start, _)) if spaces < min_spaces && !tab => Some((start, idx + 1)),
+            Some(range) => {
+                ranges.push(range);
+                Some((idx, idx + 1))
+            }
+            None => Some((idx, idx + 1)),
+        };
+
+        spaces = 0;
+        tab = false;
+    }
+
+    ranges.extend(field);
+
+    ranges
+        .into_iter()
+        .map(|(start, end)| Field {
+            text: chars[start..end].iter().collect(),
+            start,
+            end,
+        })
+        .collect()
+}
+
+/// The lines of a string or of streamed text
+pub fn text_lines(input: Value, head: Span) -> Result<LineSplitter, ShellError> {
+    let stream = match input {
+        Value::String { .. } | Value::Binary { .. } => {
+            ValueStream::from_stream(std::iter::once(input))
+        }
+        Value::Stream { stream, .. } => stream,
+        value => {
+            return Err(ShellError::UnsupportedInput(
+                format!("expected text, found {}", value.get_type()),
+                head,
+            ))
+        }
+    };
+
+    Ok(LineSplitter::new(stream))
+}
+
+/// A record of the fields of a line. Fields without a column name get one like `column3`, and
+/// columns the line is too short for have nothing in them.
+pub fn to_record(headers: &[String], fields: Vec<String>, head: Span) -> Value {
+    let len = headers.len().max(fields.len());
+
+    let cols = (0..len)
+        .map(|idx| match headers.get(idx) {
+            Some(header) => header.clone(),
+            None => format!("column{}", idx + 1),
+        })
+        .collect();
+
+    let mut vals: Vec<Value> = fields
+        .iter()
+        .map(|field| infer_value(field, head))
+        .collect();
+    vals.resize(len, Value::Nothing { span: head });
+
+    Value::Record {
+        cols,
+        vals,
+        span: head,
+    }
+}
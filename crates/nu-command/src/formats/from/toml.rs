@@ -0,0 +1,71 @@
+use nu_protocol::ast::Call;
+use nu_protocol::engine::{Command, EvaluationContext};
+use nu_protocol::{ShellError, Signature, Span, Value};
+
+use super::input::InputText;
+
+pub struct FromToml;
+
+impl Command for FromToml {
+    fn name(&self) -> &str {
+        "from toml"
+    }
+
+    fn usage(&self) -> &str {
+        "Parse text as .toml and create a record."
+    }
+
+    fn signature(&self) -> nu_protocol::Signature {
+        Signature::build("from toml")
+    }
+
+    fn run(
+        &self,
+        context: &EvaluationContext,
+        call: &Call,
+        input: Value,
+    ) -> Result<nu_protocol::Value, nu_protocol::ShellError> {
+        let input = InputText::collect(context, input, call.head)?;
+
+        match input.text.parse::<toml::Value>() {
+            Ok(value) => Ok(convert_toml(value, call.head)),
+            Err(err) => {
+                let span = match err.line_col() {
+                    Some((line, column)) => input.span_at(input.offset_of(0, line + 1, column + 1)),
+                    None => input.span,
+                };
+
+                Err(ShellError::CantParse("toml".into(), err.to_string(), span))
+            }
+        }
+    }
+}
+
+fn convert_toml(value: toml::Value, span: Span) -> Value {
+    match value {
+        toml::Value::String(val) => Value::String { val, span },
+        toml::Value::Integer(val) => Value::Int { val, span },
+        toml::Value::Float(val) => Value::Float { val, span },
+        toml::Value::Boolean(val) => Value::Bool { val, span },
+        // There is no date value yet, dates are kept as they are written
+        toml::Value::Datetime(val) => Value::String {
+            val: val.to_string(),
+            span,
+        },
+        toml::Value::Array(vals) => Value::List {
+            vals: vals
+                .into_iter()
+                .map(|val| convert_toml(val, span))
+                .collect(),
+            span,
+        },
+        toml::Value::Table(table) => {
+            let (cols, vals) = table
+                .into_iter()
+                .map(|(col, val)| (col, convert_toml(val, span)))
+                .unzip();
+
+            Value::Record { cols, vals, span }
+        }
+    }
+}
@@ -0,0 +1,146 @@
+use nu_protocol::ast::Call;
+use nu_protocol::engine::{Command, EvaluationContext};
+use nu_protocol::{ShellError, Signature, Span, Value};
+
+use super::input::InputText;
+
+pub struct FromXml;
+
+impl Command for FromXml {
+    fn name(&self) -> &str {
+        "from xml"
+    }
+
+    fn usage(&self) -> &str {
+        "Parse text as .xml and create a record."
+    }
+
+    fn signature(&self) -> nu_protocol::Signature {
+        Signature::build("from xml")
+    }
+
+    fn run(
+        &self,
+        context: &EvaluationContext,
+        call: &Call,
+        input: Value,
+    ) -> Result<nu_protocol::Value, nu_protocol::ShellError> {
+        let input = InputText::collect(context, input, call.head)?;
+
+        match roxmltree::Document::parse(&input.text) {
+            Ok(doc) => Ok(convert_element(doc.root_element(), call.head)),
+            Err(err) => {
+                let pos = err.pos();
+                let offset = input.offset_of(0, pos.row as usize, pos.col as usize);
+
+                Err(ShellError::CantParse(
+                    "xml".into(),
+                    err.to_string(),
+                    input.span_at(offset),
+                ))
+            }
+        }
+    }
+}
+
+/// Every element becomes a `{tag, attributes, content}` record, where the attributes are a record
+/// of strings and the content is a list of the child nodes. Text is a node of its own, with the
+/// text as its content and nothing as its tag and attributes. Comments and processing
+/// instructions are left out.
+fn convert_element(node: roxmltree::Node, span: Span) -> Value {
+    let mut attr_cols = vec![];
+    let mut attr_vals = vec![];
+
+    // Namespaces are only declared on the element that introduces them, so the output of
+    // `to xml` declares them in the same places
+    let parent_namespaces = node
+        .parent_element()
+        .map(|parent| parent.namespaces())
+        .unwrap_or_default();
+
+    for ns in node.namespaces() {
+        if !parent_namespaces.contains(ns) {
+            attr_cols.push(match ns.name() {
+                Some(name) => format!("xmlns:{}", name),
+                None => "xmlns".into(),
+            });
+            attr_vals.push(Value::String {
+                val: ns.uri().into(),
+                span,
+            });
+        }
+    }
+
+    for attr in node.attributes() {
+        attr_cols.push(qualified_name(node, attr.namespace(), attr.name()));
+        attr_vals.push(Value::String {
+            val: attr.value().into(),
+            span,
+        });
+    }
+
+    // Whitespace between elements is only indentation, unless the element has text of its own.
+    // In mixed content it's kept, so the text comes back the same from `to xml`.
+    let is_mixed = node
+        .children()
+        .any(|child| child.is_text() && !is_whitespace(child));
+
+    let mut content = vec![];
+    for child in node.children() {
+        if child.is_element() {
+            content.push(convert_element(child, span));
+        } else if child.is_text() && (is_mixed || !is_whitespace(child)) {
+            content.push(text_node(child.text().unwrap_or_default(), span));
+        }
+    }
+
+    Value::Record {
+        cols: vec!["tag".into(), "attributes".into(), "content".into()],
+        vals: vec![
+            Value::String {
+                val: qualified_name(node, node.tag_name().namespace(), node.tag_name().name()),
+                span,
+            },
+            Value::Record {
+                cols: attr_cols,
+                vals: attr_vals,
+                span,
+            },
+            Value::List {
+                vals: content,
+                span,
+            },
+        ],
+        span,
+    }
+}
+
+fn is_whitespace(node: roxmltree::Node) -> bool {
+    node.text()
+        .unwrap_or_default()
+        .chars()
+        .all(char::is_whitespace)
+}
+
+fn text_node(text: &str, span: Span) -> Value {
+    Value::Record {
+        cols: vec!["tag".into(), "attributes".into(), "content".into()],
+        vals: vec![
+            Value::Nothing { span },
+            Value::Nothing { span },
+            Value::String {
+                val: text.into(),
+                span,
+            },
+        ],
+        span,
+    }
+}
+
+// The name with the prefix it was written with, if it is in a namespace that has one
+fn qualified_name(node: roxmltree::Node, namespace: Option<&str>, name: &str) -> String {
+    match namespace.and_then(|uri| node.lookup_prefix(uri)) {
+        Some(prefix) => format!("{}:{}", prefix, name),
+        None => name.into(),
+    }
+}
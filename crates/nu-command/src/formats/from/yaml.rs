@@ -0,0 +1,168 @@
+use nu_protocol::ast::Call;
+use nu_protocol::engine::{Command, EvaluationContext};
+use nu_protocol::{ShellError, Signature, Span, Value};
+
+use super::input::InputText;
+
+/// `from yaml`, which also goes by the other extension of yaml files as `from yml`
+pub struct FromYaml {
+    name: &'static str,
+}
+
+impl FromYaml {
+    pub fn yaml() -> FromYaml {
+        FromYaml { name: "from yaml" }
+    }
+
+    pub fn yml() -> FromYaml {
+        FromYaml { name: "from yml" }
+    }
+}
+
+impl Command for FromYaml {
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    fn usage(&self) -> &str {
+        "Parse text as .yaml/.yml and create a table."
+    }
+
+    fn signature(&self) -> nu_protocol::Signature {
+        Signature::build(self.name)
+    }
+
+    fn run(
+        &self,
+        context: &EvaluationContext,
+        call: &Call,
+        input: Value,
+    ) -> Result<nu_protocol::Value, nu_protocol::ShellError> {
+        let input = InputText::collect(context, input, call.head)?;
+
+        let mut documents = vec![];
+        for (offset, document) in split_documents(&input.text) {
+            let value = serde_yaml::from_str(document).map_err(|err| {
+                let span = match err.location() {
+                    // The location counts characters, not bytes
+                    Some(location) => input.span_at(input.char_offset(offset, location.index())),
+                    None => input.span,
+                };
+
+                ShellError::CantParse("yaml".into(), err.to_string(), span)
+            })?;
+
+            documents.push(convert_yaml(value, call.head, &input)?);
+        }
+
+        // Every document of the input is a value, more than one of them make a list
+        if documents.len() == 1 {
+            Ok(documents.remove(0))
+        } else {
+            Ok(Value::List {
+                vals: documents,
+                span: call.head,
+            })
+        }
+    }
+}
+
+/// The documents of the text with their offsets. Every document is parsed on its own, so an error
+/// points into the document it was found in. A document starts at its `---` marker, or the
+/// directives above it, and ends at the next one or after a `...` marker.
+fn split_documents(text: &str) -> Vec<(usize, &str)> {
+    let mut documents = vec![];
+    let mut start = 0;
+    let mut offset = 0;
+    let mut directives = None;
+
+    for line in text.split_inclusive('\n') {
+        let line_start = offset;
+        offset += line.len();
+
+        if is_marker(line, "---") {
+            let end = directives.unwrap_or(line_start);
+            documents.push((start, &text[start..end]));
+            start = end;
+        }
+
+        directives = match directives {
+            _ if !line.starts_with('%') => None,
+            Some(directives) => Some(directives),
+            None => Some(line_start),
+        };
+
+        if is_marker(line, "...") {
+            documents.push((start, &text[start..offset]));
+            start = offset;
+        }
+    }
+    documents.push((start, &text[start..]));
+
+    // Whatever is around the markers may be left without any content
+    documents.retain(|(_, document)| {
+        document.lines().any(|line| {
+            let line = line.trim();
+            !line.is_empty() && !line.starts_with('#')
+        })
+    });
+
+    documents
+}
+
+fn is_marker(line: &str, marker: &str) -> bool {
+    match line.strip_prefix(marker) {
+        Some(rest) => rest.is_empty() || rest.starts_with(char::is_whitespace),
+        None => false,
+    }
+}
+
+fn convert_yaml(
+    value: serde_yaml::Value,
+    span: Span,
+    input: &InputText,
+) -> Result<Value, ShellError> {
+    Ok(match value {
+        serde_yaml::Value::Null => Value::Nothing { span },
+        serde_yaml::Value::Bool(val) => Value::Bool { val, span },
+        serde_yaml::Value::Number(number) => match number.as_i64() {
+            Some(val) => Value::Int { val, span },
+            None => Value::Float {
+                val: number.as_f64().unwrap_or(f64::NAN),
+                span,
+            },
+        },
+        serde_yaml::Value::String(val) => Value::String { val, span },
+        serde_yaml::Value::Sequence(vals) => Value::List {
+            vals: vals
+                .into_iter()
+                .map(|val| convert_yaml(val, span, input))
+                .collect::<Result<_, _>>()?,
+            span,
+        },
+        serde_yaml::Value::Mapping(mapping) => {
+            let mut cols = vec![];
+            let mut vals = vec![];
+
+            for (key, val) in mapping {
+                let col = match key {
+                    serde_yaml::Value::String(key) => key,
+                    serde_yaml::Value::Number(key) => key.to_string(),
+                    serde_yaml::Value::Bool(key) => key.to_string(),
+                    _ => {
+                        return Err(ShellError::CantParse(
+                            "yaml".into(),
+                            "only strings, numbers and booleans can be used as keys".into(),
+                            input.span,
+                        ))
+                    }
+                };
+
+                cols.push(col);
+                vals.push(convert_yaml(val, span, input)?);
+            }
+
+            Value::Record { cols, vals, span }
+        }
+    })
+}
@@ -0,0 +1,5 @@
+mod from;
+mod to;
+
+pub use from::*;
+pub use to::*;
@@ -0,0 +1,31 @@
+use nu_protocol::ast::Call;
+use nu_protocol::engine::{Command, EvaluationContext};
+use nu_protocol::{ShellError, Signature, Value};
+
+pub struct To;
+
+impl Command for To {
+    fn name(&self) -> &str {
+        "to"
+    }
+
+    fn usage(&self) -> &str {
+        "Convert structured data into a format."
+    }
+
+    fn signature(&self) -> nu_protocol::Signature {
+        Signature::build("to")
+    }
+
+    fn run(
+        &self,
+        _context: &EvaluationContext,
+        call: &Call,
+        _input: Value,
+    ) -> Result<nu_protocol::Value, nu_protocol::ShellError> {
+        Err(ShellError::UnsupportedInput(
+            "expected a format, like `to json`".into(),
+            call.head,
+        ))
+    }
+}
@@ -0,0 +1,85 @@
+use nu_protocol::{ShellError, Span, Value};
+
+/// Write a table as delimiter separated values. Rows don't need to have the same columns, the
+/// header has every column that shows up in any of them.
+pub fn to_delimited(
+    input: Value,
+    separator: u8,
+    noheaders: bool,
+    head: Span,
+) -> Result<Value, ShellError> {
+    let rows: Vec<Value> = match input {
+        Value::List { vals, .. } => vals,
+        Value::Stream { stream, .. } => stream.collect(),
+        record @ Value::Record { .. } => vec![record],
+        value => {
+            return Err(ShellError::UnsupportedInput(
+                format!("expected a table, found {}", value.get_type()),
+                head,
+            ))
+        }
+    };
+
+    let mut columns: Vec<String> = vec![];
+    for row in &rows {
+        match row {
+            Value::Record { cols, .. } => {
+                for col in cols {
+                    if !columns.contains(col) {
+                        columns.push(col.clone());
+                    }
+                }
+            }
+            Value::Error { error } => return Err(error.clone()),
+            value => {
+                return Err(ShellError::UnsupportedInput(
+                    format!("expected a table, found a row of {}", value.get_type()),
+                    value.span(),
+                ))
+            }
+        }
+    }
+
+    let mut writer = csv::WriterBuilder::new()
+        .delimiter(separator)
+        .from_writer(vec![]);
+
+    let write_error = |err: csv::Error| ShellError::CantConvert(format!("csv ({})", err), head);
+
+    if !noheaders {
+        writer.write_record(&columns).map_err(write_error)?;
+    }
+
+    for row in rows {
+        if let Value::Record { cols, vals, .. } = row {
+            let mut fields = vec![String::new(); columns.len()];
+
+            for (col, val) in cols.iter().zip(vals) {
+                if let Some(idx) = columns.iter().position(|column| column == col) {
+                    fields[idx] = field_to_string(val)?;
+                }
+            }
+
+            writer.write_record(&fields).map_err(write_error)?;
+        }
+    }
+
+    let output = writer
+        .into_inner()
+        .map_err(|err| ShellError::CantConvert(format!("csv ({})", err), head))?;
+
+    Ok(Value::String {
+        val: String::from_utf8_lossy(&output).into_owned(),
+        span: head,
+    })
+}
+
+fn field_to_string(value: Value) -> Result<String, ShellError> {
+    match value {
+        Value::String { val, .. } => Ok(val),
+        Value::Int { .. } | Value::Float { .. } | Value::Bool { .. } => Ok(value.into_string()),
+        Value::Nothing { .. } => Ok(String::new()),
+        Value::Error { error } => Err(error),
+        value => Err(ShellError::CantConvert("csv".into(), value.span())),
+    }
+}
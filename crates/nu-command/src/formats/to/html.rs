@@ -0,0 +1,153 @@
+use nu_protocol::ast::Call;
+use nu_protocol::engine::{Command, EvaluationContext};
+use nu_protocol::{ShellError, Signature, Value};
+
+use crate::tabular::{numeric_columns, table_cell, table_headers};
+
+const TABLE_STYLE: &str = "border-collapse: collapse";
+const CELL_STYLE: &str = "border: 1px solid #ccc; padding: 4px 8px";
+
+pub struct ToHtml;
+
+impl Command for ToHtml {
+    fn name(&self) -> &str {
+        "to html"
+    }
+
+    fn usage(&self) -> &str {
+        "Convert table into simple HTML."
+    }
+
+    fn signature(&self) -> nu_protocol::Signature {
+        Signature::build("to html").switch(
+            "styled",
+            "add inline styles for borders and to align numbers to the right",
+            Some('s'),
+        )
+    }
+
+    fn run(
+        &self,
+        _context: &EvaluationContext,
+        call: &Call,
+        input: Value,
+    ) -> Result<nu_protocol::Value, nu_protocol::ShellError> {
+        let styled = call.has_flag("styled");
+
+        let val = match input {
+            Value::List { vals, .. } => list_to_html(vals, styled)?,
+            Value::Stream { stream, .. } => list_to_html(stream.collect(), styled)?,
+            record @ Value::Record { .. } => table_to_html(vec![record], styled),
+            Value::Error { error } => return Err(error),
+            value => escape(&value.into_string()),
+        };
+
+        Ok(Value::String {
+            val,
+            span: call.head,
+        })
+    }
+}
+
+// A list of records is a table, any other list is a list of items
+fn list_to_html(vals: Vec<Value>, styled: bool) -> Result<String, ShellError> {
+    for val in &vals {
+        if let Value::Error { error } = val {
+            return Err(error.clone());
+        }
+    }
+
+    if !vals.is_empty() && vals.iter().all(|val| matches!(val, Value::Record { .. })) {
+        Ok(table_to_html(vals, styled))
+    } else {
+        let mut lines = vec!["<ul>".to_string()];
+        lines.extend(
+            vals.into_iter()
+                .map(|val| format!("<li>{}</li>", escape(&val.into_string()))),
+        );
+        lines.push("</ul>".into());
+
+        Ok(lines.join("\n"))
+    }
+}
+
+fn table_to_html(rows: Vec<Value>, styled: bool) -> String {
+    let headers = table_headers(&rows);
+    let cells: Vec<Vec<Value>> = rows
+        .iter()
+        .map(|row| {
+            headers
+                .iter()
+                .map(|header| table_cell(row, header))
+                .collect()
+        })
+        .collect();
+
+    let right_aligned = numeric_columns(headers.len(), &cells);
+
+    let style = |style: &str| {
+        if styled {
+            format!(" style=\"{}\"", style)
+        } else {
+            String::new()
+        }
+    };
+
+    let cell_style = |col: usize| {
+        if right_aligned[col] {
+            style(&format!("{}; text-align: right", CELL_STYLE))
+        } else {
+            style(CELL_STYLE)
+        }
+    };
+
+    let mut lines = vec![format!("<table{}>", style(TABLE_STYLE))];
+
+    lines.push("<thead>".into());
+    lines.push(format!(
+        "<tr>{}</tr>",
+        headers
+            .iter()
+            .enumerate()
+            .map(|(col, header)| format!("<th{}>{}</th>", cell_style(col), escape(header)))
+            .collect::<String>()
+    ));
+    lines.push("</thead>".into());
+
+    lines.push("<tbody>".into());
+    for row in cells {
+        lines.push(format!(
+            "<tr>{}</tr>",
+            row.into_iter()
+                .enumerate()
+                .map(|(col, cell)| format!(
+                    "<td{}>{}</td>",
+                    cell_style(col),
+                    escape(&cell.into_string())
+                ))
+                .collect::<String>()
+        ));
+    }
+    lines.push("</tbody>".into());
+
+    lines.push("</table>".into());
+
+    lines.join("\n")
+}
+
+fn escape(text: &str) -> String {
+    let mut output = String::with_capacity(text.len());
+
+    for c in text.chars() {
+        match c {
+            '&' => output.push_str("&amp;"),
+            '<' => output.push_str("&lt;"),
+            '>' => output.push_str("&gt;"),
+            '"' => output.push_str("&quot;"),
+            '\'' => output.push_str("&#39;"),
+            c => output.push(c),
+        }
+    }
+
+    output
+}
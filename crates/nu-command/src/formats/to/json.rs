@@ -0,0 +1,105 @@
+use nu_engine::eval_expression;
+use nu_protocol::ast::Call;
+use nu_protocol::engine::{Command, EvaluationContext};
+use nu_protocol::{ShellError, Signature, SyntaxShape, Value};
+use serde::Serialize;
+
+pub struct ToJson;
+
+impl Command for ToJson {
+    fn name(&self) -> &str {
+        "to json"
+    }
+
+    fn usage(&self) -> &str {
+        "Convert structured data into json text."
+    }
+
+    fn signature(&self) -> nu_protocol::Signature {
+        Signature::build("to json")
+            .switch("raw", "write the json without any whitespace", Some('r'))
+            .named(
+                "indent",
+                SyntaxShape::Int,
+                "the number of spaces to indent with (2 by default)",
+                Some('i'),
+            )
+    }
+
+    fn run(
+        &self,
+        context: &EvaluationContext,
+        call: &Call,
+        input: Value,
+    ) -> Result<nu_protocol::Value, nu_protocol::ShellError> {
+        let json = value_to_json(input)?;
+
+        let output = if call.has_flag("raw") {
+            serde_json::to_string(&json)
+        } else {
+            let indent = match call.get_flag_expr("indent") {
+                Some(expr) => match eval_expression(context, expr)? {
+                    Value::Int { val, .. } if val >= 0 => val as usize,
+                    val => return Err(ShellError::CantConvert("indent".into(), val.span())),
+                },
+                None => 2,
+            };
+
+            let indent = vec![b' '; indent];
+            let mut output = vec![];
+            let mut serializer = serde_json::Serializer::with_formatter(
+                &mut output,
+                serde_json::ser::PrettyFormatter::with_indent(&indent),
+            );
+
+            json.serialize(&mut serializer)
+                .map(|_| String::from_utf8_lossy(&output).into_owned())
+        };
+
+        match output {
+            Ok(val) => Ok(Value::String {
+                val,
+                span: call.head,
+            }),
+            Err(err) => Err(ShellError::CantConvert(
+                format!("json ({})", err),
+                call.head,
+            )),
+        }
+    }
+}
+
+fn value_to_json(value: Value) -> Result<serde_json::Value, ShellError> {
+    Ok(match value {
+        Value::Nothing { .. } => serde_json::Value::Null,
+        Value::Bool { val, .. } => serde_json::Value::Bool(val),
+        Value::Int { val, .. } => serde_json::Value::from(val),
+        Value::Float { val, span } => match serde_json::Number::from_f64(val) {
+            Some(number) => serde_json::Value::Number(number),
+            None => return Err(ShellError::CantConvert("json".into(), span)),
+        },
+        Value::String { val, .. } => serde_json::Value::String(val),
+        Value::Binary { val, .. } => serde_json::Value::from(val),
+        Value::List { vals, .. } => values_to_json(vals)?,
+        Value::Stream { stream, .. } => values_to_json(stream)?,
+        Value::Range { val, .. } => values_to_json(*val)?,
+        Value::Record { cols, vals, .. } => {
+            let mut object = serde_json::Map::new();
+
+            for (col, val) in cols.into_iter().zip(vals) {
+                object.insert(col, value_to_json(val)?);
+            }
+
+            serde_json::Value::Object(object)
+        }
+        Value::Error { error } => return Err(error),
+        value => return Err(ShellError::CantConvert("json".into(), value.span())),
+    })
+}
+
+fn values_to_json(vals: impl IntoIterator<Item = Value>) -> Result<serde_json::Value, ShellError> {
+    vals.into_iter()
+        .map(value_to_json)
+        .collect::<Result<_, _>>()
+        .map(serde_json::Value::Array)
+}
@@ -0,0 +1,148 @@
+use nu_protocol::ast::Call;
+use nu_protocol::engine::{Command, EvaluationContext};
+use nu_protocol::{ShellError, Signature, Value};
+
+use crate::tabular::{numeric_columns, table_cell, table_headers};
+
+pub struct ToMd;
+
+impl Command for ToMd {
+    fn name(&self) -> &str {
+        "to md"
+    }
+
+    fn usage(&self) -> &str {
+        "Convert table into simple Markdown."
+    }
+
+    fn signature(&self) -> nu_protocol::Signature {
+        Signature::build("to md").switch(
+            "pretty",
+            "pad the cells so the columns line up",
+            Some('p'),
+        )
+    }
+
+    fn run(
+        &self,
+        _context: &EvaluationContext,
+        call: &Call,
+        input: Value,
+    ) -> Result<nu_protocol::Value, nu_protocol::ShellError> {
+        let pretty = call.has_flag("pretty");
+
+        let val = match input {
+            Value::List { vals, .. } => list_to_md(vals, pretty)?,
+            Value::Stream { stream, .. } => list_to_md(stream.collect(), pretty)?,
+            record @ Value::Record { .. } => table_to_md(vec![record], pretty),
+            Value::Error { error } => return Err(error),
+            value => escape(&value.into_string()),
+        };
+
+        Ok(Value::String {
+            val,
+            span: call.head,
+        })
+    }
+}
+
+// A list of records is a table, any other list gets a line for every item
+fn list_to_md(vals: Vec<Value>, pretty: bool) -> Result<String, ShellError> {
+    for val in &vals {
+        if let Value::Error { error } = val {
+            return Err(error.clone());
+        }
+    }
+
+    if !vals.is_empty() && vals.iter().all(|val| matches!(val, Value::Record { .. })) {
+        Ok(table_to_md(vals, pretty))
+    } else {
+        Ok(vals
+            .into_iter()
+            .map(|val| format!("* {}", escape(&val.into_string())))
+            .collect::<Vec<_>>()
+            .join("\n"))
+    }
+}
+
+fn table_to_md(rows: Vec<Value>, pretty: bool) -> String {
+    let headers = table_headers(&rows);
+    let cells: Vec<Vec<Value>> = rows
+        .iter()
+        .map(|row| {
+            headers
+                .iter()
+                .map(|header| table_cell(row, header))
+                .collect()
+        })
+        .collect();
+
+    let right_aligned = numeric_columns(headers.len(), &cells);
+
+    let headers: Vec<String> = headers.iter().map(|header| escape(header)).collect();
+    let cells: Vec<Vec<String>> = cells
+        .into_iter()
+        .map(|row| {
+            row.into_iter()
+                .map(|cell| escape(&cell.into_string()))
+                .collect()
+        })
+        .collect();
+
+    // The separator needs at least three dashes
+    let widths: Vec<usize> = (0..headers.len())
+        .map(|col| {
+            if pretty {
+                cells
+                    .iter()
+                    .map(|row| row[col].chars().count())
+                    .chain(std::iter::once(headers[col].chars().count()))
+                    .max()
+                    .unwrap_or(0)
+                    .max(3)
+            } else {
+                3
+            }
+        })
+        .collect();
+
+    let line = |row: &[String]| {
+        let cells: Vec<String> = row
+            .iter()
+            .enumerate()
+            .map(|(col, cell)| {
+                if !pretty {
+                    cell.clone()
+                } else if right_aligned[col] {
+                    format!("{:>width$}", cell, width = widths[col])
+                } else {
+                    format!("{:<width$}", cell, width = widths[col])
+                }
+            })
+            .collect();
+
+        format!("| {} |", cells.join(" | "))
+    };
+
+    let separator: Vec<String> = widths
+        .iter()
+        .zip(&right_aligned)
+        .map(|(width, right_aligned)| {
+            if *right_aligned {
+                format!("{}:", "-".repeat(width - 1))
+            } else {
+                "-".repeat(*width)
+            }
+        })
+        .collect();
+
+    let mut lines = vec![line(&headers), format!("| {} |", separator.join(" | "))];
+    lines.extend(cells.iter().map(|row| line(row)));
+
+    lines.join("\n")
+}
+
+// Pipes would end the cell and newlines the row
+fn escape(text: &str) -> String {
+    text.replace('|', "\\|").replace('\n', "<br>")
+}
@@ -0,0 +1,22 @@
+mod command;
+mod csv;
+mod delimited;
+mod html;
+mod json;
+mod md;
+mod nuon;
+mod toml;
+mod tsv;
+mod xml;
+mod yaml;
+
+pub use self::csv::ToCsv;
+pub use self::toml::ToToml;
+pub use command::To;
+pub use html::ToHtml;
+pub use json::ToJson;
+pub use md::ToMd;
+pub use nuon::ToNuon;
+pub use tsv::ToTsv;
+pub use xml::ToXml;
+pub use yaml::ToYaml;
@@ -0,0 +1,190 @@
+use nu_protocol::ast::{Call, RangeInclusion};
+use nu_protocol::engine::{Command, EvaluationContext};
+use nu_protocol::{Range, ShellError, Signature, Value};
+
+pub struct ToNuon;
+
+impl Command for ToNuon {
+    fn name(&self) -> &str {
+        "to nuon"
+    }
+
+    fn usage(&self) -> &str {
+        "Convert structured data into nuon text."
+    }
+
+    fn signature(&self) -> nu_protocol::Signature {
+        Signature::build("to nuon")
+    }
+
+    fn run(
+        &self,
+        _context: &EvaluationContext,
+        call: &Call,
+        input: Value,
+    ) -> Result<nu_protocol::Value, nu_protocol::ShellError> {
+        Ok(Value::String {
+            val: value_to_nuon(input)?,
+            span: call.head,
+        })
+    }
+}
+
+/// Write a value with the same literal syntax the parser reads, so `from nuon` gives it back as
+/// it was
+fn value_to_nuon(value: Value) -> Result<String, ShellError> {
+    Ok(match value {
+        Value::Nothing { .. } => "$nothing".into(),
+        Value::Bool { val, .. } => format!("${}", val),
+        Value::Int { val, .. } => val.to_string(),
+        Value::Float { val, span } => {
+            if !val.is_finite() {
+                return Err(ShellError::CantConvert("nuon".into(), span));
+            }
+
+            // Debug formatting always keeps the decimal point, so the number is read back as
+            // a float
+            format!("{:?}", val)
+        }
+        Value::String { val, .. } => quote_string(&val),
+        Value::Binary { val, .. } => {
+            let digits: Vec<_> = val.iter().map(|byte| format!("{:02x}", byte)).collect();
+
+            format!("0x[{}]", digits.join(" "))
+        }
+        Value::List { vals, .. } => list_to_nuon(vals)?,
+        Value::Stream { stream, .. } => list_to_nuon(stream.collect())?,
+        Value::Range { val, .. } => range_to_nuon(*val)?,
+        Value::Record { cols, vals, .. } => {
+            let fields = cols
+                .iter()
+                .zip(vals)
+                .map(|(col, val)| Ok(format!("{}: {}", column_to_nuon(col), value_to_nuon(val)?)))
+                .collect::<Result<Vec<_>, ShellError>>()?;
+
+            format!("{{{}}}", fields.join(", "))
+        }
+        Value::Error { error } => return Err(error),
+        value => {
+            return Err(ShellError::UnsupportedInput(
+                format!("{} can't be converted to nuon", value.get_type()),
+                value.span(),
+            ))
+        }
+    })
+}
+
+// A list of records that all have the same columns is written as a table literal
+fn list_to_nuon(vals: Vec<Value>) -> Result<String, ShellError> {
+    let cols = match vals.first() {
+        Some(Value::Record { cols, .. }) if !cols.is_empty() => cols.clone(),
+        _ => vec![],
+    };
+
+    let is_table = !cols.is_empty()
+        && vals
+            .iter()
+            .all(|val| matches!(val, Value::Record { cols: row_cols, .. } if *row_cols == cols));
+
+    if is_table {
+        let headers: Vec<_> = cols.iter().map(|col| column_to_nuon(col)).collect();
+
+        let mut rows = vec![];
+        for val in vals {
+            if let Value::Record { vals, .. } = val {
+                rows.push(format!("[{}]", values_to_nuon(vals)?));
+            }
+        }
+
+        Ok(format!("[[{}]; {}]", headers.join(", "), rows.join(", ")))
+    } else {
+        Ok(format!("[{}]", values_to_nuon(vals)?))
+    }
+}
+
+fn values_to_nuon(vals: Vec<Value>) -> Result<String, ShellError> {
+    Ok(vals
+        .into_iter()
+        .map(value_to_nuon)
+        .collect::<Result<Vec<_>, _>>()?
+        .join(", "))
+}
+
+fn range_to_nuon(range: Range) -> Result<String, ShellError> {
+    let operator = match range.inclusion {
+        RangeInclusion::Inclusive => "..",
+        RangeInclusion::RightExclusive => "..<",
+    };
+
+    // The step is only written when it isn't the one the range would have anyway
+    let default_step = match (&range.from, &range.incr, &range.to) {
+        (
+            Value::Int { val: from, .. },
+            Value::Int { val: incr, .. },
+            Value::Int { val: to, .. },
+        ) => (from <= to && *incr == 1) || (from > to && *incr == -1),
+        _ => false,
+    };
+
+    let from = value_to_nuon(range.from.clone())?;
+    let to = value_to_nuon(range.to)?;
+
+    if default_step {
+        Ok(format!("{}{}{}", from, operator, to))
+    } else {
+        let next = range.from.add(range.from.span(), &range.incr)?;
+
+        Ok(format!(
+            "{}..{}{}{}",
+            from,
+            value_to_nuon(next)?,
+            operator,
+            to
+        ))
+    }
+}
+
+// Column names are left bare when the parser would read them back as the same string
+fn column_to_nuon(col: &str) -> String {
+    let is_bare = matches!(col.chars().next(), Some(c) if c.is_ascii_alphabetic() || c == '_')
+        && col
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+
+    if is_bare {
+        col.into()
+    } else {
+        quote_string(col)
+    }
+}
+
+// Quoted strings have no escapes, everything up to the closing quote is kept as it is. A string
+// with both quote characters is split into parts that each leave one of them out, and written as
+// their concatenation.
+fn quote_string(val: &str) -> String {
+    let mut parts = vec![];
+    let mut part = String::new();
+
+    for c in val.chars() {
+        if (c == '"' && part.contains('\'')) || (c == '\'' && part.contains('"')) {
+            parts.push(quote_part(&part));
+            part.clear();
+        }
+        part.push(c);
+    }
+    parts.push(quote_part(&part));
+
+    if parts.len() == 1 {
+        parts.remove(0)
+    } else {
+        format!("({})", parts.join(" + "))
+    }
+}
+
+fn quote_part(part: &str) -> String {
+    if part.contains('"') {
+        format!("'{}'", part)
+    } else {
+        format!("\"{}\"", part)
+    }
+}
@@ -0,0 +1,83 @@
+use nu_protocol::ast::Call;
+use nu_protocol::engine::{Command, EvaluationContext};
+use nu_protocol::{ShellError, Signature, Value};
+
+pub struct ToToml;
+
+impl Command for ToToml {
+    fn name(&self) -> &str {
+        "to toml"
+    }
+
+    fn usage(&self) -> &str {
+        "Convert a record into .toml text."
+    }
+
+    fn signature(&self) -> nu_protocol::Signature {
+        Signature::build("to toml")
+    }
+
+    fn run(
+        &self,
+        _context: &EvaluationContext,
+        call: &Call,
+        input: Value,
+    ) -> Result<nu_protocol::Value, nu_protocol::ShellError> {
+        // A toml document is always a table
+        let toml = match input {
+            record @ Value::Record { .. } => value_to_toml(record)?,
+            value => {
+                return Err(ShellError::UnsupportedInput(
+                    format!("expected a record, found {}", value.get_type()),
+                    call.head,
+                ))
+            }
+        };
+
+        match toml::to_string(&toml) {
+            Ok(val) => Ok(Value::String {
+                val,
+                span: call.head,
+            }),
+            Err(err) => Err(ShellError::CantConvert(
+                format!("toml ({})", err),
+                call.head,
+            )),
+        }
+    }
+}
+
+fn value_to_toml(value: Value) -> Result<toml::Value, ShellError> {
+    Ok(match value {
+        Value::Bool { val, .. } => toml::Value::Boolean(val),
+        Value::Int { val, .. } => toml::Value::Integer(val),
+        Value::Float { val, .. } => toml::Value::Float(val),
+        Value::String { val, .. } => toml::Value::String(val),
+        Value::List { vals, .. } => values_to_toml(vals)?,
+        Value::Stream { stream, .. } => values_to_toml(stream)?,
+        Value::Range { val, .. } => values_to_toml(*val)?,
+        Value::Record { cols, vals, .. } => {
+            let mut table = toml::value::Table::new();
+
+            for (col, val) in cols.into_iter().zip(vals) {
+                table.insert(col, value_to_toml(val)?);
+            }
+
+            toml::Value::Table(table)
+        }
+        Value::Error { error } => return Err(error),
+        value => {
+            return Err(ShellError::UnsupportedInput(
+                format!("{} can't be converted to toml", value.get_type()),
+                value.span(),
+            ))
+        }
+    })
+}
+
+fn values_to_toml(vals: impl IntoIterator<Item = Value>) -> Result<toml::Value, ShellError> {
+    vals.into_iter()
+        .map(value_to_toml)
+        .collect::<Result<_, _>>()
+        .map(toml::Value::Array)
+}
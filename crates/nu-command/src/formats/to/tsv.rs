@@ -0,0 +1,41 @@
+use nu_protocol::ast::Call;
+use nu_protocol::engine::{Command, EvaluationContext};
+use nu_protocol::{Signature, SyntaxShape, Value};
+
+use crate::formats::from::separator_flag;
+
+use super::delimited::to_delimited;
+
+pub struct ToTsv;
+
+impl Command for ToTsv {
+    fn name(&self) -> &str {
+        "to tsv"
+    }
+
+    fn usage(&self) -> &str {
+        "Convert a table into .tsv text."
+    }
+
+    fn signature(&self) -> nu_protocol::Signature {
+        Signature::build("to tsv")
+            .switch("noheaders", "don't write the column names", Some('n'))
+            .named(
+                "separator",
+                SyntaxShape::String,
+                "a character to separate columns, defaults to a tab",
+                Some('s'),
+            )
+    }
+
+    fn run(
+        &self,
+        context: &EvaluationContext,
+        call: &Call,
+        input: Value,
+    ) -> Result<nu_protocol::Value, nu_protocol::ShellError> {
+        let separator = separator_flag(context, call, b'\t')?;
+
+        to_delimited(input, separator, call.has_flag("noheaders"), call.head)
+    }
+}
@@ -0,0 +1,237 @@
+use nu_engine::eval_expression;
+use nu_protocol::ast::Call;
+use nu_protocol::engine::{Command, EvaluationContext};
+use nu_protocol::{ShellError, Signature, Span, SyntaxShape, Value};
+
+pub struct ToXml;
+
+impl Command for ToXml {
+    fn name(&self) -> &str {
+        "to xml"
+    }
+
+    fn usage(&self) -> &str {
+        "Convert a record of the tag, attributes and content of an element into .xml text."
+    }
+
+    fn signature(&self) -> nu_protocol::Signature {
+        Signature::build("to xml").named(
+            "pretty",
+            SyntaxShape::Int,
+            "put every element on its own line, indented by the given number of spaces",
+            Some('p'),
+        )
+    }
+
+    fn run(
+        &self,
+        context: &EvaluationContext,
+        call: &Call,
+        input: Value,
+    ) -> Result<nu_protocol::Value, nu_protocol::ShellError> {
+        let indent = match call.get_flag_expr("pretty") {
+            Some(expr) => match eval_expression(context, expr)? {
+                Value::Int { val, .. } if val >= 0 => Some(val as usize),
+                val => return Err(ShellError::CantConvert("indent".into(), val.span())),
+            },
+            None => None,
+        };
+
+        let mut writer = XmlWriter {
+            output: String::new(),
+            indent,
+        };
+
+        writer.write_node(&input, 0)?;
+
+        Ok(Value::String {
+            val: writer.output,
+            span: call.head,
+        })
+    }
+}
+
+struct XmlWriter {
+    output: String,
+    indent: Option<usize>,
+}
+
+impl XmlWriter {
+    // Takes the same `{tag, attributes, content}` records `from xml` creates. A node without a
+    // tag is text, and plain strings in the content are text as well.
+    fn write_node(&mut self, node: &Value, depth: usize) -> Result<(), ShellError> {
+        let (tag, attributes, content) = match node {
+            Value::String { val, .. } => {
+                self.output.push_str(&escape(val, false));
+                return Ok(());
+            }
+            Value::Record { cols, vals, span } => {
+                let field = |name: &str| {
+                    cols.iter()
+                        .position(|col| col == name)
+                        .map(|idx| &vals[idx])
+                        .unwrap_or(&Value::Nothing { span: *span })
+                        .clone()
+                };
+
+                (field("tag"), field("attributes"), field("content"))
+            }
+            Value::Error { error } => return Err(error.clone()),
+            _ => return Err(unsupported(node, "an xml node")),
+        };
+
+        let tag = match tag {
+            Value::String { val, span } => check_name(val, span)?,
+            Value::Nothing { .. } => {
+                return match content {
+                    Value::String { val, .. } => {
+                        self.output.push_str(&escape(&val, false));
+                        Ok(())
+                    }
+                    content => Err(unsupported(&content, "xml text")),
+                }
+            }
+            tag => return Err(unsupported(&tag, "an xml tag")),
+        };
+
+        self.output.push('<');
+        self.output.push_str(&tag);
+
+        match attributes {
+            Value::Record { cols, vals, span } => {
+                for (col, val) in cols.iter().zip(vals) {
+                    let col = check_name(col.clone(), span)?;
+                    let val = match val {
+                        Value::String { val, .. } => val,
+                        Value::Int { val, .. } => val.to_string(),
+                        Value::Float { val, .. } => val.to_string(),
+                        Value::Bool { val, .. } => val.to_string(),
+                        val => return Err(unsupported(&val, "an xml attribute")),
+                    };
+
+                    self.output
+                        .push_str(&format!(" {}=\"{}\"", col, escape(&val, true)));
+                }
+            }
+            Value::Nothing { .. } => {}
+            attributes => return Err(unsupported(&attributes, "xml attributes")),
+        }
+
+        let children = match content {
+            Value::List { vals, .. } => vals,
+            Value::Stream { stream, .. } => stream.collect(),
+            Value::String { val, span } => vec![Value::String { val, span }],
+            Value::Nothing { .. } => vec![],
+            content => return Err(unsupported(&content, "xml content")),
+        };
+
+        if children.is_empty() {
+            self.output.push_str("/>");
+            return Ok(());
+        }
+
+        self.output.push('>');
+
+        // Whitespace around text is part of the text, so only elements that contain nothing but
+        // other elements get their children put on separate lines
+        let has_text = children.iter().any(|child| match child {
+            Value::String { .. } => true,
+            Value::Record { cols, vals, .. } => cols
+                .iter()
+                .zip(vals)
+                .any(|(col, val)| col == "tag" && matches!(val, Value::Nothing { .. })),
+            _ => false,
+        });
+
+        for child in &children {
+            if !has_text {
+                self.newline(depth + 1);
+            }
+            self.write_node(child, depth + 1)?;
+        }
+
+        if !has_text {
+            self.newline(depth);
+        }
+
+        self.output.push_str(&format!("</{}>", tag));
+
+        Ok(())
+    }
+
+    fn newline(&mut self, depth: usize) {
+        if let Some(indent) = self.indent {
+            self.output.push('\n');
+            self.output.push_str(&" ".repeat(indent * depth));
+        }
+    }
+}
+
+// Tags and attributes have to be xml names, otherwise the element can't be read back
+fn check_name(name: String, span: Span) -> Result<String, ShellError> {
+    let mut chars = name.chars();
+
+    let is_name =
+        matches!(chars.next(), Some(c) if is_name_start_char(c)) && chars.all(is_name_char);
+
+    if is_name {
+        Ok(name)
+    } else {
+        Err(ShellError::UnsupportedInput(
+            format!("'{}' is not a valid xml name", name),
+            span,
+        ))
+    }
+}
+
+// The start characters of the Name production in the xml spec
+fn is_name_start_char(c: char) -> bool {
+    matches!(c,
+        ':' | 'A'..='Z' | '_' | 'a'..='z'
+        | '\u{C0}'..='\u{D6}'
+        | '\u{D8}'..='\u{F6}'
+        | '\u{F8}'..='\u{2FF}'
+        | '\u{370}'..='\u{37D}'
+        | '\u{37F}'..='\u{1FFF}'
+        | '\u{200C}'..='\u{200D}'
+        | '\u{2070}'..='\u{218F}'
+        | '\u{2C00}'..='\u{2FEF}'
+        | '\u{3001}'..='\u{D7FF}'
+        | '\u{F900}'..='\u{FDCF}'
+        | '\u{FDF0}'..='\u{FFFD}'
+        | '\u{10000}'..='\u{EFFFF}'
+    )
+}
+
+fn is_name_char(c: char) -> bool {
+    is_name_start_char(c)
+        || matches!(c,
+            '-' | '.' | '0'..='9'
+            | '\u{B7}'
+            | '\u{300}'..='\u{36F}'
+            | '\u{203F}'..='\u{2040}'
+        )
+}
+
+fn escape(text: &str, in_attribute: bool) -> String {
+    let mut output = String::with_capacity(text.len());
+
+    for c in text.chars() {
+        match c {
+            '&' => output.push_str("&amp;"),
+            '<' => output.push_str("&lt;"),
+            '>' => output.push_str("&gt;"),
+            '"' if in_attribute => output.push_str("&quot;"),
+            c => output.push(c),
+        }
+    }
+
+    output
+}
+
+fn unsupported(value: &Value, expected: &str) -> ShellError {
+    ShellError::UnsupportedInput(
+        format!("{} can't be converted to {}", value.get_type(), expected),
+        value.span(),
+    )
+}
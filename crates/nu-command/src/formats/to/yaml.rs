@@ -0,0 +1,86 @@
+use nu_protocol::ast::Call;
+use nu_protocol::engine::{Command, EvaluationContext};
+use nu_protocol::{ShellError, Signature, Span, Value};
+
+/// `to yaml`, which also goes by the other extension of yaml files as `to yml`
+pub struct ToYaml {
+    name: &'static str,
+}
+
+impl ToYaml {
+    pub fn yaml() -> ToYaml {
+        ToYaml { name: "to yaml" }
+    }
+
+    pub fn yml() -> ToYaml {
+        ToYaml { name: "to yml" }
+    }
+}
+
+impl Command for ToYaml {
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    fn usage(&self) -> &str {
+        "Convert structured data into .yaml text."
+    }
+
+    fn signature(&self) -> nu_protocol::Signature {
+        Signature::build(self.name)
+    }
+
+    fn run(
+        &self,
+        _context: &EvaluationContext,
+        call: &Call,
+        input: Value,
+    ) -> Result<nu_protocol::Value, nu_protocol::ShellError> {
+        to_yaml(input, call.head)
+    }
+}
+
+fn to_yaml(input: Value, head: Span) -> Result<Value, ShellError> {
+    let yaml = value_to_yaml(input)?;
+
+    match serde_yaml::to_string(&yaml) {
+        Ok(val) => Ok(Value::String { val, span: head }),
+        Err(err) => Err(ShellError::CantConvert(format!("yaml ({})", err), head)),
+    }
+}
+
+fn value_to_yaml(value: Value) -> Result<serde_yaml::Value, ShellError> {
+    Ok(match value {
+        Value::Nothing { .. } => serde_yaml::Value::Null,
+        Value::Bool { val, .. } => serde_yaml::Value::Bool(val),
+        Value::Int { val, .. } => serde_yaml::Value::Number(val.into()),
+        Value::Float { val, .. } => serde_yaml::Value::Number(val.into()),
+        Value::String { val, .. } => serde_yaml::Value::String(val),
+        Value::List { vals, .. } => values_to_yaml(vals)?,
+        Value::Stream { stream, .. } => values_to_yaml(stream)?,
+        Value::Range { val, .. } => values_to_yaml(*val)?,
+        Value::Record { cols, vals, .. } => {
+            let mut mapping = serde_yaml::Mapping::new();
+
+            for (col, val) in cols.into_iter().zip(vals) {
+                mapping.insert(serde_yaml::Value::String(col), value_to_yaml(val)?);
+            }
+
+            serde_yaml::Value::Mapping(mapping)
+        }
+        Value::Error { error } => return Err(error),
+        value => {
+            return Err(ShellError::UnsupportedInput(
+                format!("{} can't be converted to yaml", value.get_type()),
+                value.span(),
+            ))
+        }
+    })
+}
+
+fn values_to_yaml(vals: impl IntoIterator<Item = Value>) -> Result<serde_yaml::Value, ShellError> {
+    vals.into_iter()
+        .map(value_to_yaml)
+        .collect::<Result<_, _>>()
+        .map(serde_yaml::Value::Sequence)
+}
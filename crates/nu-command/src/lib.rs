@@ -4,8 +4,10 @@ mod env;
 mod experimental;
 mod filesystem;
 mod filters;
+mod formats;
 mod strings;
 mod system;
+mod tabular;
 mod viewers;
 
 pub use core_commands::*;
@@ -14,6 +16,7 @@ pub use env::*;
 pub use experimental::*;
 pub use filesystem::*;
 pub use filters::*;
+pub use formats::*;
 pub use strings::*;
 pub use system::*;
 pub use viewers::*;
@@ -0,0 +1,96 @@
+use nu_engine::eval_expression;
+use nu_protocol::ast::Call;
+use nu_protocol::engine::{Command, EvaluationContext};
+use nu_protocol::{IntoValueStream, ShellError, Signature, SyntaxShape, Value};
+
+use crate::formats::{aligned_fields, header_columns, merge_spaced_columns, text_lines, to_record};
+
+/// The number of lines after the header line used to find column names with spaces in them
+const SAMPLE_LINES: usize = 100;
+
+pub struct DetectColumns;
+
+impl Command for DetectColumns {
+    fn name(&self) -> &str {
+        "detect columns"
+    }
+
+    fn usage(&self) -> &str {
+        "Split aligned text into columns, using where the column names are in the header line."
+    }
+
+    fn signature(&self) -> nu_protocol::Signature {
+        Signature::build("detect columns")
+            .named(
+                "skip",
+                SyntaxShape::Int,
+                "the number of lines to skip before the header line",
+                Some('s'),
+            )
+            .switch(
+                "noheaders",
+                "don't treat the first line as column names",
+                Some('n'),
+            )
+    }
+
+    fn run(
+        &self,
+        context: &EvaluationContext,
+        call: &Call,
+        input: Value,
+    ) -> Result<nu_protocol::Value, nu_protocol::ShellError> {
+        let skip = match call.get_flag_expr("skip") {
+            Some(expr) => match eval_expression(context, expr)? {
+                Value::Int { val, .. } if val >= 0 => val as usize,
+                val => return Err(ShellError::CantConvert("skip".into(), val.span())),
+            },
+            None => 0,
+        };
+        let noheaders = call.has_flag("noheaders");
+
+        let head = call.head;
+        let mut lines = text_lines(input, head)?.skip(skip);
+
+        let first = match lines.next() {
+            Some(line) => line,
+            None => {
+                return Ok(Value::List {
+                    vals: vec![],
+                    span: head,
+                })
+            }
+        };
+        let header = first.as_string()?;
+
+        // Column names are often separated by a single space, like in the output of `ps`, so the
+        // first lines under them tell which ones are a single name with a space in it
+        let sample: Vec<Value> = lines.by_ref().take(SAMPLE_LINES).collect();
+        let sample_text: Vec<String> = sample
+            .iter()
+            .filter_map(|line| line.as_string().ok())
+            .collect();
+        let columns = merge_spaced_columns(header_columns(&header, 1), &sample_text);
+
+        let (headers, first): (Vec<String>, Option<Value>) = if noheaders {
+            (vec![], Some(first))
+        } else {
+            let headers = columns.iter().map(|column| column.name.clone()).collect();
+            (headers, None)
+        };
+
+        let records = first
+            .into_iter()
+            .chain(sample)
+            .chain(lines)
+            .map(move |line| match line.as_string() {
+                Ok(line) => to_record(&headers, aligned_fields(&line, &columns), head),
+                Err(error) => Value::Error { error },
+            });
+
+        Ok(Value::Stream {
+            stream: records.into_value_stream(),
+            span: head,
+        })
+    }
+}
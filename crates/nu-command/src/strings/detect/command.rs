@@ -0,0 +1,31 @@
+use nu_protocol::ast::Call;
+use nu_protocol::engine::{Command, EvaluationContext};
+use nu_protocol::{ShellError, Signature, Value};
+
+pub struct Detect;
+
+impl Command for Detect {
+    fn name(&self) -> &str {
+        "detect"
+    }
+
+    fn usage(&self) -> &str {
+        "Detect the structure of text."
+    }
+
+    fn signature(&self) -> nu_protocol::Signature {
+        Signature::build("detect")
+    }
+
+    fn run(
+        &self,
+        _context: &EvaluationContext,
+        call: &Call,
+        _input: Value,
+    ) -> Result<nu_protocol::Value, nu_protocol::ShellError> {
+        Err(ShellError::UnsupportedInput(
+            "expected what to detect, like `detect columns`".into(),
+            call.head,
+        ))
+    }
+}
@@ -0,0 +1,5 @@
+mod columns;
+mod command;
+
+pub use columns::DetectColumns;
+pub use command::Detect;
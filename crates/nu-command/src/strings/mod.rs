@@ -1,3 +1,7 @@
 mod build_string;
+mod detect;
+mod parse;
 
 pub use build_string::BuildString;
+pub use detect::{Detect, DetectColumns};
+pub use parse::Parse;
@@ -0,0 +1,157 @@
+use nu_engine::eval_expression;
+use nu_protocol::ast::Call;
+use nu_protocol::engine::{Command, EvaluationContext};
+use nu_protocol::{IntoValueStream, ShellError, Signature, Span, SyntaxShape, Value};
+use regex::Regex;
+
+use crate::formats::text_lines;
+
+pub struct Parse;
+
+impl Command for Parse {
+    fn name(&self) -> &str {
+        "parse"
+    }
+
+    fn usage(&self) -> &str {
+        "Parse columns out of every line of text, using a pattern like `{user}@{host}`."
+    }
+
+    fn signature(&self) -> nu_protocol::Signature {
+        Signature::build("parse")
+            .required(
+                "pattern",
+                SyntaxShape::String,
+                "the pattern, where every `{name}` is a column",
+            )
+            .switch(
+                "regex",
+                "use a regular expression, where every capture group is a column",
+                Some('r'),
+            )
+            .switch(
+                "strict",
+                "make lines that don't match an error instead of skipping them",
+                Some('s'),
+            )
+    }
+
+    fn run(
+        &self,
+        context: &EvaluationContext,
+        call: &Call,
+        input: Value,
+    ) -> Result<nu_protocol::Value, nu_protocol::ShellError> {
+        let pattern_expr = &call.positional[0];
+        let pattern_span = pattern_expr.span;
+        let pattern = eval_expression(context, pattern_expr)?.as_string()?;
+        let strict = call.has_flag("strict");
+
+        let (regex, columns) = if call.has_flag("regex") {
+            let regex = compile(&pattern, pattern_span)?;
+            let columns = regex_columns(&regex);
+            (regex, columns)
+        } else {
+            let (regex, columns) = pattern_to_regex(&pattern, pattern_span)?;
+            (compile(&regex, pattern_span)?, columns)
+        };
+
+        let head = call.head;
+        let records = text_lines(input, head)?
+            .enumerate()
+            .filter_map(move |(idx, line)| {
+                let line = match line.as_string() {
+                    Ok(line) => line,
+                    Err(error) => return Some(Value::Error { error }),
+                };
+
+                match regex.captures(&line) {
+                    Some(captures) => Some(Value::Record {
+                        cols: columns.clone(),
+                        vals: (1..=columns.len())
+                            .map(|group| match captures.get(group) {
+                                Some(capture) => Value::String {
+                                    val: capture.as_str().to_string(),
+                                    span: head,
+                                },
+                                None => Value::Nothing { span: head },
+                            })
+                            .collect(),
+                        span: head,
+                    }),
+                    None if strict => Some(Value::Error {
+                        error: ShellError::CantParse(
+                            "pattern".into(),
+                            format!("line {} doesn't match: {}", idx + 1, line),
+                            pattern_span,
+                        ),
+                    }),
+                    None => None,
+                }
+            });
+
+        Ok(Value::Stream {
+            stream: records.into_value_stream(),
+            span: head,
+        })
+    }
+}
+
+fn compile(regex: &str, span: Span) -> Result<Regex, ShellError> {
+    Regex::new(regex).map_err(|err| ShellError::CantParse("regex".into(), err.to_string(), span))
+}
+
+// Named groups are columns with their name, other groups get one like `capture2`
+fn regex_columns(regex: &Regex) -> Vec<String> {
+    regex
+        .capture_names()
+        .enumerate()
+        .skip(1)
+        .map(|(idx, name)| match name {
+            Some(name) => name.to_string(),
+            None => format!("capture{}", idx),
+        })
+        .collect()
+}
+
+// Every `{name}` becomes a group that matches as little as it can, and the rest of the pattern has
+// to match the text as it is. The pattern has to match the whole line. Groups aren't named in the
+// regex, so column names can be anything a regex group name couldn't be.
+fn pattern_to_regex(pattern: &str, span: Span) -> Result<(String, Vec<String>), ShellError> {
+    let mut regex = String::from("^");
+    let mut columns = vec![];
+    let mut rest = pattern;
+
+    while let Some(start) = rest.find('{') {
+        regex.push_str(&regex::escape(&rest[..start]));
+
+        let end = match rest[start..].find('}') {
+            Some(end) => start + end,
+            None => {
+                return Err(ShellError::CantParse(
+                    "pattern".into(),
+                    "`{` without a closing `}`".into(),
+                    span,
+                ))
+            }
+        };
+
+        let name = rest[start + 1..end].trim();
+        if name.is_empty() {
+            return Err(ShellError::CantParse(
+                "pattern".into(),
+                "columns need a name, like `{name}`".into(),
+                span,
+            ));
+        }
+
+        columns.push(name.to_string());
+        regex.push_str("(.*?)");
+        rest = &rest[end + 1..];
+    }
+
+    regex.push_str(&regex::escape(rest));
+    regex.push('$');
+
+    Ok((regex, columns))
+}
@@ -0,0 +1,40 @@
+use nu_protocol::ast::Call;
+use nu_protocol::engine::{Command, EvaluationContext};
+use nu_protocol::{ShellError, Signature, Value};
+
+pub struct Complete;
+
+impl Command for Complete {
+    fn name(&self) -> &str {
+        "complete"
+    }
+
+    fn usage(&self) -> &str {
+        "Complete the external piped in, returning its stdout, stderr and exit code."
+    }
+
+    fn signature(&self) -> nu_protocol::Signature {
+        Signature::build("complete")
+    }
+
+    fn run(
+        &self,
+        _context: &EvaluationContext,
+        call: &Call,
+        input: Value,
+    ) -> Result<nu_protocol::Value, nu_protocol::ShellError> {
+        // The parser marks the external piped into here, so it was already run to completion, and
+        // it doesn't let anything else be piped into this command
+        match input {
+            Value::Record { cols, vals, .. } => Ok(Value::Record {
+                cols,
+                vals,
+                span: call.head,
+            }),
+            _ => Err(ShellError::UnsupportedInput(
+                "complete only works on external commands".into(),
+                call.head,
+            )),
+        }
+    }
+}
@@ -1,5 +1,7 @@
 mod benchmark;
+mod complete;
 mod run_external;
 
 pub use benchmark::Benchmark;
+pub use complete::Complete;
 pub use run_external::{External, ExternalCommand};
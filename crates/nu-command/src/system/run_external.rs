@@ -1,21 +1,21 @@
-use std::borrow::Cow;
-use std::cell::RefCell;
-use std::env;
-use std::io::{BufRead, BufReader, Write};
-use std::process::{ChildStdin, Command as CommandSys, Stdio};
-use std::rc::Rc;
-use std::sync::mpsc;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command as CommandSys, ExitStatus, Stdio};
+use std::thread::JoinHandle;
 
 use nu_protocol::{
-    ast::{Call, Expression},
+    ast::{Call, Expr, Expression},
     engine::{Command, EvaluationContext},
     ShellError, Signature, SyntaxShape, Value,
 };
-use nu_protocol::{Span, ValueStream};
+use nu_protocol::{Process, RawStream, Span, StdinFeeder};
 
-use nu_engine::eval_expression;
+use nu_engine::{current_dir, env_to_strings, eval_expression};
 
-const OUTPUT_BUFFER_SIZE: usize = 8192;
+/// Environment variable with the exit code of the last external command
+const LAST_EXIT_CODE_ENV: &str = "LAST_EXIT_CODE";
 
 pub struct External;
 
@@ -31,6 +31,23 @@ impl Command for External {
     fn signature(&self) -> nu_protocol::Signature {
         Signature::build("run_external")
             .switch("last_expression", "last_expression", None)
+            .switch(
+                "complete",
+                "wait for the command and return its output and exit code",
+                None,
+            )
+            .named(
+                "redirect-stdout",
+                SyntaxShape::String,
+                "write the output to a file",
+                None,
+            )
+            .named(
+                "redirect-stderr",
+                SyntaxShape::String,
+                "write the error output to a file",
+                None,
+            )
             .rest("rest", SyntaxShape::Any, "external command to run")
     }
 
@@ -50,6 +67,9 @@ pub struct ExternalCommand<'call, 'contex> {
     pub args: &'call [Expression],
     pub context: &'contex EvaluationContext,
     pub last_expression: bool,
+    pub complete: bool,
+    pub redirect_stdout: Option<&'call Expression>,
+    pub redirect_stderr: Option<&'call Expression>,
 }
 
 impl<'call, 'contex> ExternalCommand<'call, 'contex> {
@@ -66,6 +86,9 @@ impl<'call, 'contex> ExternalCommand<'call, 'contex> {
             args: &call.positional[1..],
             context,
             last_expression: call.has_flag("last_expression"),
+            complete: call.has_flag("complete"),
+            redirect_stdout: call.get_flag_expr("redirect-stdout"),
+            redirect_stderr: call.get_flag_expr("redirect-stderr"),
         })
     }
 
@@ -74,38 +97,78 @@ impl<'call, 'contex> ExternalCommand<'call, 'contex> {
         value.as_string()
     }
 
-    pub fn get_args(&self) -> Vec<String> {
-        self.args
-            .iter()
-            .filter_map(|expr| eval_expression(self.context, expr).ok())
-            .filter_map(|value| value.as_string().ok())
-            .collect()
+    pub fn get_args(&self, cwd: &Path) -> Result<Vec<String>, ShellError> {
+        let mut args = vec![];
+
+        for expr in self.args {
+            match &expr.expr {
+                Expr::GlobPattern(pattern) => args.extend(expand_glob(pattern, cwd)),
+                _ => args.push(eval_expression(self.context, expr)?.as_string()?),
+            }
+        }
+
+        Ok(args)
     }
 
     pub fn run_with_input(&self, input: Value) -> Result<Value, ShellError> {
-        let mut process = self.create_command();
+        let cwd = current_dir(self.context)?;
+        let envs = env_to_strings(self.context)?;
 
-        // TODO. We don't have a way to know the current directory
-        // This should be information from the EvaluationContex or EngineState
-        let path = env::current_dir().unwrap();
-        process.current_dir(path);
+        let mut process = self.create_command(&cwd, &envs)?;
+        process.current_dir(&cwd);
 
-        let envs = self.context.stack.get_env_vars();
+        // The stack holds the whole environment, including what was inherited from the parent
+        // process, so hidden variables must not leak through
+        process.env_clear();
         process.envs(envs);
 
-        // If the external is not the last command, its output will get piped
-        // either as a string or binary
-        if !self.last_expression {
+        // If the external is not the last command, its output gets piped to the next one
+        if !self.last_expression || self.complete {
             process.stdout(Stdio::piped());
         }
 
-        // If there is an input from the pipeline. The stdin from the process
-        // is piped so it can be used to send the input information
-        if let Value::String { .. } = input {
-            process.stdin(Stdio::piped());
+        if self.complete {
+            process.stderr(Stdio::piped());
+        }
+
+        let (stdout_file, stderr_file) = self.open_redirections(&cwd)?;
+        let stdout_redirected = stdout_file.is_some();
+
+        if let Some(file) = stdout_file {
+            process.stdout(file);
+        }
+
+        if let Some(file) = stderr_file {
+            process.stderr(file);
         }
 
-        if let Value::Stream { .. } = input {
+        // The output of another external is connected straight to the stdin of this one, and that
+        // external is waited for along with this one. Any other input from the pipeline is written
+        // to the stdin of the process.
+        let mut upstream = vec![];
+
+        let input: Option<Box<dyn Iterator<Item = Vec<u8>>>> = match input {
+            Value::RawStream { stream, .. } => match stream.take_pipe() {
+                Some((pipe, processes)) => {
+                    process.stdin(Stdio::from(pipe));
+                    upstream = processes;
+                    None
+                }
+                None => Some(Box::new(stream)),
+            },
+            Value::String { val, .. } => Some(Box::new(std::iter::once(val.into_bytes()))),
+            Value::Binary { val, .. } => Some(Box::new(std::iter::once(val))),
+            Value::Stream { stream, .. } => {
+                Some(Box::new(stream.filter_map(|value| match value {
+                    Value::String { val, .. } => Some(val.into_bytes()),
+                    Value::Binary { val, .. } => Some(val),
+                    _ => None,
+                })))
+            }
+            _ => None,
+        };
+
+        if input.is_some() {
             process.stdin(Stdio::piped());
         }
 
@@ -115,40 +178,19 @@ impl<'call, 'contex> ExternalCommand<'call, 'contex> {
                 self.name.span,
             )),
             Ok(mut child) => {
-                // if there is a string or a stream, that is sent to the pipe std
-                match input {
-                    Value::String { val, span: _ } => {
-                        if let Some(mut stdin_write) = child.stdin.take() {
-                            self.write_to_stdin(&mut stdin_write, val.as_bytes())?
-                        }
-                    }
-                    Value::Binary { val, span: _ } => {
-                        if let Some(mut stdin_write) = child.stdin.take() {
-                            self.write_to_stdin(&mut stdin_write, &val)?
-                        }
-                    }
-                    Value::Stream { stream, span: _ } => {
-                        if let Some(mut stdin_write) = child.stdin.take() {
-                            for value in stream {
-                                match value {
-                                    Value::String { val, span: _ } => {
-                                        self.write_to_stdin(&mut stdin_write, val.as_bytes())?
-                                    }
-                                    Value::Binary { val, span: _ } => {
-                                        self.write_to_stdin(&mut stdin_write, &val)?
-                                    }
-                                    _ => continue,
-                                }
-                            }
-                        }
-                    }
-                    _ => (),
+                let feeder = match (input, child.stdin.take()) {
+                    (Some(input), Some(stdin)) => Some(StdinFeeder::new(input, stdin)),
+                    _ => None,
+                };
+
+                if self.complete {
+                    return self.wait_for_output(child, feeder, upstream);
                 }
 
-                // If this external is not the last expression, then its output is piped to a channel
-                // and we create a ValueStream that can be consumed
-                let value = if !self.last_expression {
-                    let (tx, rx) = mpsc::channel();
+                // If this external is not the last expression, its stdout is passed on as a raw
+                // stream, which hands the input over while it's read. The exit codes are recorded
+                // once the stream has been read.
+                if !self.last_expression && !stdout_redirected {
                     let stdout = child.stdout.take().ok_or_else(|| {
                         ShellError::ExternalCommand(
                             "Error taking stdout from external".to_string(),
@@ -156,126 +198,319 @@ impl<'call, 'contex> ExternalCommand<'call, 'contex> {
                         )
                     })?;
 
-                    std::thread::spawn(move || {
-                        // Stdout is read using the Buffer reader. It will do so until there is an
-                        // error or there are no more bytes to read
-                        let mut buf_read = BufReader::with_capacity(OUTPUT_BUFFER_SIZE, stdout);
-                        while let Ok(bytes) = buf_read.fill_buf() {
-                            if bytes.is_empty() {
-                                break;
-                            }
-
-                            // The Cow generated from the function represents the conversion
-                            // from bytes to String. If no replacements are required, then the
-                            // borrowed value is a proper UTF-8 string. The Owned option represents
-                            // a string where the values had to be replaced, thus marking it as bytes
-                            let data = match String::from_utf8_lossy(bytes) {
-                                Cow::Borrowed(s) => Data::String(s.into()),
-                                Cow::Owned(_) => Data::Bytes(bytes.to_vec()),
-                            };
-
-                            let length = bytes.len();
-                            buf_read.consume(length);
-
-                            match tx.send(data) {
-                                Ok(_) => continue,
-                                Err(_) => break,
-                            }
-                        }
+                    let context = self.context.clone();
+                    let span = self.name.span;
+
+                    let mut processes = upstream;
+                    processes.push(Process::new(child, move |status| {
+                        set_last_exit_code(&context, status, span);
+                    }));
+
+                    let stream = RawStream::from_processes(stdout, processes);
+
+                    return Ok(Value::RawStream {
+                        stream: match feeder {
+                            Some(feeder) => stream.with_feeder(feeder),
+                            None => stream,
+                        },
+                        span: self.name.span,
                     });
+                }
 
-                    // The ValueStream is consumed by the next expression in the pipeline
-                    Value::Stream {
-                        stream: ValueStream(Rc::new(RefCell::new(ChannelReceiver::new(rx)))),
-                        span: Span::unknown(),
-                    }
-                } else {
-                    Value::nothing()
-                };
+                // Nothing reads the output of the process, so the input can be handed over as fast
+                // as the process takes it
+                if let Some(feeder) = feeder {
+                    feeder.feed_all();
+                }
+
+                let status = child.wait();
 
-                match child.wait() {
+                for process in upstream {
+                    process.wait();
+                }
+
+                match status {
                     Err(err) => Err(ShellError::ExternalCommand(
                         format!("{}", err),
                         self.name.span,
                     )),
-                    Ok(_) => Ok(value),
+                    Ok(status) => {
+                        let exit_code = self.set_last_exit_code(status);
+
+                        if !status.success() {
+                            Err(ShellError::ExternalCommand(
+                                format!("exited with code {}", exit_code),
+                                self.name.span,
+                            ))
+                        } else {
+                            Ok(Value::nothing())
+                        }
+                    }
                 }
             }
         }
     }
 
-    fn create_command(&self) -> CommandSys {
-        // in all the other cases shell out
-        if cfg!(windows) {
-            //TODO. This should be modifiable from the config file.
-            // We could give the option to call from powershell
-            // for minimal builds cwd is unused
-            let mut process = CommandSys::new("cmd");
-            process.arg("/c");
-            process.arg(&self.get_name().unwrap());
-            for arg in self.get_args() {
-                // Clean the args before we use them:
-                // https://stackoverflow.com/questions/1200235/how-to-pass-a-quoted-pipe-character-to-cmd-exe
-                // cmd.exe needs to have a caret to escape a pipe
-                let arg = arg.replace("|", "^|");
-                process.arg(&arg);
+    /// Create the files the output of the command is redirected to. With `out+err>` both outputs
+    /// share the same file, so they don't overwrite each other.
+    fn open_redirections(&self, cwd: &Path) -> Result<(Option<File>, Option<File>), ShellError> {
+        let stdout_path = self.redirection_path(self.redirect_stdout, cwd)?;
+        let stderr_path = self.redirection_path(self.redirect_stderr, cwd)?;
+
+        let stdout_file = match &stdout_path {
+            Some((path, span)) => Some(create_file(path, *span)?),
+            None => None,
+        };
+
+        let stderr_file = match (&stderr_path, &stdout_file) {
+            (Some((path, span)), Some(stdout_file))
+                if Some(path) == stdout_path.as_ref().map(|(path, _)| path) =>
+            {
+                Some(
+                    stdout_file
+                        .try_clone()
+                        .map_err(|err| ShellError::IOError(err.to_string(), *span))?,
+                )
             }
-            process
-        } else {
-            let cmd_with_args = vec![self.get_name().unwrap(), self.get_args().join(" ")].join(" ");
-            let mut process = CommandSys::new("sh");
-            process.arg("-c").arg(cmd_with_args);
-            process
+            (Some((path, span)), _) => Some(create_file(path, *span)?),
+            (None, _) => None,
+        };
+
+        Ok((stdout_file, stderr_file))
+    }
+
+    fn redirection_path(
+        &self,
+        expr: Option<&Expression>,
+        cwd: &Path,
+    ) -> Result<Option<(PathBuf, Span)>, ShellError> {
+        match expr {
+            Some(expr) => {
+                let path = eval_expression(self.context, expr)?.as_string()?;
+                Ok(Some((cwd.join(path), expr.span)))
+            }
+            None => Ok(None),
         }
     }
 
-    fn write_to_stdin(&self, stdin_write: &mut ChildStdin, val: &[u8]) -> Result<(), ShellError> {
-        if stdin_write.write(val).is_err() {
-            Err(ShellError::ExternalCommand(
-                "Error writing input to stdin".to_string(),
+    fn wait_for_output(
+        &self,
+        mut child: Child,
+        feeder: Option<StdinFeeder>,
+        upstream: Vec<Process>,
+    ) -> Result<Value, ShellError> {
+        // The outputs are read while the input is handed over, so the process never waits for
+        // them to be read
+        let stdout = child.stdout.take().map(read_to_end_in_background);
+        let stderr = child.stderr.take().map(read_to_end_in_background);
+
+        if let Some(feeder) = feeder {
+            feeder.feed_all();
+        }
+
+        let status = child
+            .wait()
+            .map_err(|err| ShellError::ExternalCommand(format!("{}", err), self.name.span))?;
+
+        for process in upstream {
+            process.wait();
+        }
+
+        let exit_code = self.set_last_exit_code(status);
+        let span = self.name.span;
+
+        let stdout = stdout
+            .and_then(|reader| reader.join().ok())
+            .unwrap_or_default();
+        let stderr = stderr
+            .and_then(|reader| reader.join().ok())
+            .unwrap_or_default();
+
+        Ok(Value::Record {
+            cols: vec!["stdout".into(), "stderr".into(), "exit_code".into()],
+            vals: vec![
+                Value::String {
+                    val: String::from_utf8_lossy(&stdout).to_string(),
+                    span,
+                },
+                Value::String {
+                    val: String::from_utf8_lossy(&stderr).to_string(),
+                    span,
+                },
+                Value::Int {
+                    val: exit_code,
+                    span,
+                },
+            ],
+            span,
+        })
+    }
+
+    fn set_last_exit_code(&self, status: ExitStatus) -> i64 {
+        set_last_exit_code(self.context, status, self.name.span)
+    }
+
+    // The program is spawned directly, with every argument passed as is, so nothing gets
+    // re-interpreted by a shell. Anyone who needs a shell can still run `^sh -c "..."`.
+    fn create_command(
+        &self,
+        cwd: &Path,
+        envs: &HashMap<String, String>,
+    ) -> Result<CommandSys, ShellError> {
+        let name = self.get_name()?;
+
+        let path_var = envs.iter().find_map(|(var, val)| {
+            let is_path = if cfg!(windows) {
+                var.eq_ignore_ascii_case("PATH")
+            } else {
+                var == "PATH"
+            };
+
+            if is_path {
+                Some(val)
+            } else {
+                None
+            }
+        });
+
+        match find_executable(&name, cwd, path_var) {
+            Some(executable) => {
+                let mut process = CommandSys::new(executable);
+                process.args(self.get_args(cwd)?);
+
+                Ok(process)
+            }
+            // The builtins of cmd.exe, like `dir`, aren't programs, so they are still run through it
+            None if cfg!(windows) && is_cmd_builtin(&name) => {
+                let mut process = CommandSys::new("cmd");
+                process.arg("/c");
+                process.arg(&name);
+                for arg in self.get_args(cwd)? {
+                    // Clean the args before we use them:
+                    // https://stackoverflow.com/questions/1200235/how-to-pass-a-quoted-pipe-character-to-cmd-exe
+                    // cmd.exe needs to have a caret to escape a pipe
+                    process.arg(arg.replace("|", "^|"));
+                }
+
+                Ok(process)
+            }
+            None => Err(ShellError::ExternalCommand(
+                format!("{} not found", name),
                 self.name.span,
-            ))
-        } else {
-            Ok(())
+            )),
         }
     }
 }
 
-// The piped data from stdout from the external command can be either String
-// or binary. We use this enum to pass the data from the spawned process
-enum Data {
-    String(String),
-    Bytes(Vec<u8>),
+fn is_cmd_builtin(name: &str) -> bool {
+    const BUILTINS: [&str; 36] = [
+        "assoc", "break", "call", "cd", "chdir", "cls", "color", "copy", "date", "del", "dir",
+        "echo", "endlocal", "erase", "for", "ftype", "goto", "if", "md", "mkdir", "mklink", "move",
+        "path", "pause", "popd", "prompt", "pushd", "rd", "rem", "ren", "rename", "rmdir", "set",
+        "setlocal", "start", "type",
+    ];
+
+    BUILTINS
+        .iter()
+        .any(|builtin| name.eq_ignore_ascii_case(builtin))
 }
 
-// Receiver used for the ValueStream
-// It implements iterator so it can be used as a ValueStream
-struct ChannelReceiver {
-    rx: mpsc::Receiver<Data>,
+fn read_to_end_in_background(mut pipe: impl Read + Send + 'static) -> JoinHandle<Vec<u8>> {
+    std::thread::spawn(move || {
+        let mut output = vec![];
+        let _ = pipe.read_to_end(&mut output);
+        output
+    })
 }
 
-impl ChannelReceiver {
-    pub fn new(rx: mpsc::Receiver<Data>) -> Self {
-        Self { rx }
+fn set_last_exit_code(context: &EvaluationContext, status: ExitStatus, span: Span) -> i64 {
+    // Processes killed by a signal have no exit code
+    let exit_code = status.code().unwrap_or(-1) as i64;
+
+    context.add_env_var(
+        LAST_EXIT_CODE_ENV.into(),
+        Value::Int {
+            val: exit_code,
+            span,
+        },
+    );
+
+    exit_code
+}
+
+fn create_file(path: &Path, span: Span) -> Result<File, ShellError> {
+    File::create(path).map_err(|err| ShellError::IOError(err.to_string(), span))
+}
+
+/// Look the program up like a shell would: a name with a path separator is a path relative to the
+/// working directory, anything else is searched for in the directories of PATH
+fn find_executable(name: &str, cwd: &Path, path_var: Option<&String>) -> Option<PathBuf> {
+    if name.contains('/') || name.contains(std::path::MAIN_SEPARATOR) {
+        let path = cwd.join(name);
+
+        return if is_executable(&path) {
+            Some(path)
+        } else {
+            None
+        };
     }
+
+    std::env::split_paths(path_var?)
+        .flat_map(|dir| executable_candidates(&cwd.join(dir).join(name)))
+        .find(|path| is_executable(path))
 }
 
-impl Iterator for ChannelReceiver {
-    type Item = Value;
-
-    fn next(&mut self) -> Option<Self::Item> {
-        match self.rx.recv() {
-            Ok(v) => match v {
-                Data::String(s) => Some(Value::String {
-                    val: s,
-                    span: Span::unknown(),
-                }),
-                Data::Bytes(b) => Some(Value::Binary {
-                    val: b,
-                    span: Span::unknown(),
-                }),
-            },
-            Err(_) => None,
-        }
+fn executable_candidates(path: &Path) -> Vec<PathBuf> {
+    if cfg!(windows) && path.extension().is_none() {
+        ["exe", "com", "cmd", "bat"]
+            .iter()
+            .map(|ext| path.with_extension(ext))
+            .collect()
+    } else {
+        vec![path.to_path_buf()]
+    }
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+
+    match std::fs::metadata(path) {
+        Ok(metadata) => metadata.is_file() && metadata.permissions().mode() & 0o111 != 0,
+        Err(_) => false,
+    }
+}
+
+#[cfg(not(unix))]
+fn is_executable(path: &Path) -> bool {
+    path.is_file()
+}
+
+/// Expand a glob relative to the working directory. Like in most shells, a glob that doesn't match
+/// anything is passed on as it is.
+fn expand_glob(arg: &str, cwd: &Path) -> Vec<String> {
+    let is_relative = Path::new(arg).is_relative();
+    let pattern = Path::new(&glob::Pattern::escape(&cwd.to_string_lossy())).join(arg);
+
+    let expanded: Vec<String> = match glob::glob(&pattern.to_string_lossy()) {
+        Ok(paths) => paths
+            .filter_map(|path| path.ok())
+            .map(|path| {
+                if is_relative {
+                    path.strip_prefix(cwd).unwrap_or(&path)
+                } else {
+                    &path
+                }
+                .to_string_lossy()
+                .to_string()
+            })
+            .collect(),
+        Err(_) => vec![],
+    };
+
+    if expanded.is_empty() {
+        vec![arg.to_string()]
+    } else {
+        expanded
     }
 }
@@ -0,0 +1,55 @@
+// How rows of values are laid out in the columns of a table, for the table view as well as the
+// `to md` and `to html` converters
+
+use nu_protocol::ast::PathMember;
+use nu_protocol::{Span, Value};
+
+/// The headers of `to md` and `to html` are the columns of all of the rows, in the order they first
+/// show up in. The table view streams its rows, so it takes the columns of the first one instead.
+pub(crate) fn table_headers(rows: &[Value]) -> Vec<String> {
+    let mut headers: Vec<String> = vec![];
+
+    for row in rows {
+        for col in row.columns() {
+            if !headers.contains(&col) {
+                headers.push(col);
+            }
+        }
+    }
+
+    headers
+}
+
+/// Columns that only have numbers in them, which get aligned to the right
+pub(crate) fn numeric_columns(columns: usize, cells: &[Vec<Value>]) -> Vec<bool> {
+    (0..columns)
+        .map(|col| {
+            let mut has_numbers = false;
+
+            for row in cells {
+                match row[col] {
+                    Value::Int { .. } | Value::Float { .. } => has_numbers = true,
+                    Value::Nothing { .. } => {}
+                    _ => return false,
+                }
+            }
+
+            has_numbers
+        })
+        .collect()
+}
+
+/// The value of a row in a column of the table, which is nothing if the row doesn't have it
+pub(crate) fn table_cell(item: &Value, header: &str) -> Value {
+    let result = match item {
+        Value::Record { .. } => item.clone().follow_cell_path(&[PathMember::String {
+            val: header.into(),
+            span: Span::unknown(),
+        }]),
+        _ => Ok(item.clone()),
+    };
+
+    result.unwrap_or(Value::Nothing {
+        span: Span::unknown(),
+    })
+}
@@ -1,10 +1,12 @@
 use std::collections::HashMap;
 
-use nu_protocol::ast::{Call, PathMember};
+use nu_protocol::ast::Call;
 use nu_protocol::engine::{Command, EvaluationContext};
-use nu_protocol::{Signature, Span, Value};
+use nu_protocol::{Signature, Value};
 use nu_table::StyledString;
 
+use crate::tabular::table_cell;
+
 pub struct Table;
 
 //NOTE: this is not a real implementation :D. It's just a simple one to test with until we port the real one.
@@ -64,6 +66,7 @@ impl Command for Table {
 fn convert_to_table(iter: impl IntoIterator<Item = Value>) -> Option<nu_table::Table> {
     let mut iter = iter.into_iter().peekable();
 
+    // The rows are streamed, so the headers are the columns of the first row
     if let Some(first) = iter.peek() {
         let mut headers = first.columns();
 
@@ -80,20 +83,7 @@ fn convert_to_table(iter: impl IntoIterator<Item = Value>) -> Option<nu_table::T
                 row.push(item.into_string())
             } else {
                 for header in headers.iter().skip(1) {
-                    let result = match item {
-                        Value::Record { .. } => {
-                            item.clone().follow_cell_path(&[PathMember::String {
-                                val: header.into(),
-                                span: Span::unknown(),
-                            }])
-                        }
-                        _ => Ok(item.clone()),
-                    };
-
-                    match result {
-                        Ok(value) => row.push(value.into_string()),
-                        Err(_) => row.push(String::new()),
-                    }
+                    row.push(table_cell(&item, header).into_string());
                 }
             }
 
@@ -0,0 +1,86 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use nu_parser::LIB_DIRS_ENV;
+use nu_protocol::engine::{EngineState, EvaluationContext, Stack};
+use nu_protocol::{ShellError, Span, Value};
+
+/// Translate an environment variable coming from the operating system into a value, using the
+/// conversion registered for it, if any
+pub fn env_from_string(engine_state: &EngineState, name: &str, val: &str, span: Span) -> Value {
+    match engine_state.get_env_conversion(name) {
+        Some(conversion) => (conversion.from_string)(val, span),
+        None => Value::String {
+            val: val.to_string(),
+            span,
+        },
+    }
+}
+
+/// Translate an environment variable back into the string form external commands expect
+pub fn env_to_string(
+    engine_state: &EngineState,
+    name: &str,
+    val: &Value,
+) -> Result<String, ShellError> {
+    if let Some(conversion) = engine_state.get_env_conversion(name) {
+        return (conversion.to_string)(val);
+    }
+
+    match val {
+        Value::String { val, .. } => Ok(val.clone()),
+        Value::Bool { .. } | Value::Int { .. } | Value::Float { .. } => {
+            Ok(val.clone().into_string())
+        }
+        _ => Err(ShellError::EnvVarNotAString(name.to_string(), val.span())),
+    }
+}
+
+/// All the environment variables of the current stack, translated for an external command.
+/// Variables without a conversion that can't be a string are left out with a warning, so one
+/// structured value doesn't stop every external from running.
+pub fn env_to_strings(context: &EvaluationContext) -> Result<HashMap<String, String>, ShellError> {
+    let engine_state = context.engine_state.borrow();
+    let mut env_strings = HashMap::new();
+
+    for (name, val) in context.stack.get_env_vars() {
+        match env_to_string(&engine_state, &name, &val) {
+            Ok(val) => {
+                env_strings.insert(name, val);
+            }
+            Err(err @ ShellError::EnvVarNotAString(..)) => context.add_warning(err),
+            Err(err) => return Err(err),
+        }
+    }
+
+    Ok(env_strings)
+}
+
+/// Environment variable holding the working directory of the engine
+pub const PWD_ENV: &str = "PWD";
+
+/// The working directory relative paths are resolved against. It lives in the environment, so it
+/// follows the same scoping rules as any other variable. Falls back to the working directory of
+/// the process if it was never set.
+pub fn current_dir(context: &EvaluationContext) -> Result<PathBuf, ShellError> {
+    match context.stack.get_env_var(PWD_ENV) {
+        Some(pwd) => Ok(PathBuf::from(pwd.as_string()?)),
+        None => std::env::current_dir().map_err(|err| {
+            ShellError::InternalError(format!("Can't get current directory: {}", err))
+        }),
+    }
+}
+
+/// The directories module files are looked up in, from `NU_LIB_DIRS` in the environment of the
+/// stack. It can be a list of directories, or a string of them joined like `PATH`.
+pub fn lib_dirs(stack: &Stack) -> Vec<PathBuf> {
+    match stack.get_env_var(LIB_DIRS_ENV) {
+        Some(Value::List { vals, .. }) => vals
+            .iter()
+            .filter_map(|val| val.as_string().ok())
+            .map(PathBuf::from)
+            .collect(),
+        Some(Value::String { val, .. }) => std::env::split_paths(&val).collect(),
+        _ => vec![],
+    }
+}
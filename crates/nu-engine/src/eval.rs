@@ -1,6 +1,8 @@
-use nu_protocol::ast::{Block, Call, Expr, Expression, Operator, Statement};
-use nu_protocol::engine::EvaluationContext;
-use nu_protocol::{Range, ShellError, Span, Type, Value};
+use nu_protocol::ast::{
+    Block, Call, Expr, Expression, ExternalArgument, Operator, Redirection, Statement,
+};
+use nu_protocol::engine::{EvaluationContext, ENV_VARIABLE_ID};
+use nu_protocol::{Range, ShellError, Span, Type, Value, VarId};
 
 pub fn eval_operator(op: &Expression) -> Result<Operator, ShellError> {
     match op {
@@ -14,7 +16,20 @@ pub fn eval_operator(op: &Expression) -> Result<Operator, ShellError> {
     }
 }
 
-fn eval_call(context: &EvaluationContext, call: &Call, input: Value) -> Result<Value, ShellError> {
+pub fn eval_call(
+    context: &EvaluationContext,
+    call: &Call,
+    input: Value,
+) -> Result<Value, ShellError> {
+    // Internal commands work with values, so this is where the output of an external gets decoded
+    let input = match input {
+        Value::RawStream { stream, span } => Value::Stream {
+            stream: stream.into_value_stream(span),
+            span,
+        },
+        x => x,
+    };
+
     let engine_state = context.engine_state.borrow();
     let decl = engine_state.get_decl(call.decl_id);
     if let Some(block_id) = decl.get_block_id() {
@@ -62,7 +77,14 @@ fn eval_call(context: &EvaluationContext, call: &Call, input: Value) -> Result<V
         }
         let engine_state = state.engine_state.borrow();
         let block = engine_state.get_block(block_id);
-        eval_block(&state, block, input)
+        let result = eval_block(&state, block, input);
+
+        // A block that failed leaves the environment of the caller as it was
+        if block.redirect_env && result.is_ok() {
+            state.stack.redirect_env(&context.stack);
+        }
+
+        result
     } else {
         decl.run(context, call, input)
     }
@@ -70,42 +92,119 @@ fn eval_call(context: &EvaluationContext, call: &Call, input: Value) -> Result<V
 
 fn eval_external(
     context: &EvaluationContext,
-    name: &Span,
-    args: &[Span],
+    head: &Expression,
+    args: &[ExternalArgument],
+    redirections: &[(Redirection, Expression)],
     input: Value,
     last_expression: bool,
+    complete: bool,
 ) -> Result<Value, ShellError> {
     let engine_state = context.engine_state.borrow();
 
     let decl_id = engine_state
         .find_decl("run_external".as_bytes())
-        .ok_or_else(|| ShellError::ExternalNotSupported(*name))?;
+        .ok_or_else(|| ShellError::ExternalNotSupported(head.span))?;
 
     let command = engine_state.get_decl(decl_id);
 
     let mut call = Call::new();
-    call.positional = [*name]
-        .iter()
-        .chain(args.iter())
-        .map(|span| {
-            let contents = engine_state.get_span_contents(span);
-            let val = String::from_utf8_lossy(contents);
-            Expression {
-                expr: Expr::String(val.into()),
-                span: *span,
-                ty: Type::String,
-                custom_completion: None,
+    call.positional.push(external_arg(
+        eval_expression(context, head)?.as_string()?,
+        head.span,
+    ));
+
+    // Arguments reach the external as plain strings, except globs, which it expands itself
+    for arg in args {
+        match arg {
+            ExternalArgument::Regular(
+                expr @ Expression {
+                    expr: Expr::GlobPattern(_),
+                    ..
+                },
+            ) => call.positional.push(expr.clone()),
+            ExternalArgument::Regular(expr) => {
+                let val = external_arg_string(eval_expression(context, expr)?)?;
+                call.positional.push(external_arg(val, expr.span));
+            }
+            ExternalArgument::Spread(_, expr) => {
+                let vals = match eval_expression(context, expr)? {
+                    Value::List { vals, .. } => vals,
+                    Value::Stream { stream, .. } => stream.collect(),
+                    val => return Err(ShellError::CantConvert("list".into(), val.span())),
+                };
+
+                for val in vals {
+                    let val = external_arg_string(val)?;
+                    call.positional.push(external_arg(val, expr.span));
+                }
             }
-        })
-        .collect();
+        }
+    }
+
+    for (redirection, target) in redirections {
+        let path = external_arg(
+            external_arg_string(eval_expression(context, target)?)?,
+            target.span,
+        );
+
+        if *redirection != Redirection::Stderr {
+            call.named
+                .push(("redirect-stdout".into(), Some(path.clone())));
+        }
+
+        if *redirection != Redirection::Stdout {
+            call.named.push(("redirect-stderr".into(), Some(path)));
+        }
+    }
 
     if last_expression {
         call.named.push(("last_expression".into(), None))
     }
 
+    if complete {
+        call.named.push(("complete".into(), None))
+    }
+
     command.run(context, &call, input)
 }
 
+fn external_arg(val: String, span: Span) -> Expression {
+    Expression {
+        expr: Expr::String(val),
+        span,
+        ty: Type::String,
+        custom_completion: None,
+    }
+}
+
+fn external_arg_string(val: Value) -> Result<String, ShellError> {
+    match val {
+        Value::String { val, .. } => Ok(val),
+        Value::Int { .. } | Value::Float { .. } | Value::Bool { .. } => Ok(val.into_string()),
+        Value::Stream { .. } | Value::RawStream { .. } => Ok(val.into_string()),
+        val => Err(ShellError::CantConvert("string".into(), val.span())),
+    }
+}
+
+fn eval_variable(
+    context: &EvaluationContext,
+    var_id: VarId,
+    span: Span,
+) -> Result<Value, ShellError> {
+    if var_id == ENV_VARIABLE_ID {
+        let mut env_vars: Vec<_> = context.stack.get_env_vars().into_iter().collect();
+        env_vars.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+        let (cols, vals) = env_vars.into_iter().unzip();
+
+        Ok(Value::Record { cols, vals, span })
+    } else {
+        context
+            .get_var(var_id)
+            .map_err(move |_| ShellError::VariableNotFoundAtRuntime(span))
+    }
+}
+
 pub fn eval_expression(
     context: &EvaluationContext,
     expr: &Expression,
@@ -123,6 +222,11 @@ pub fn eval_expression(
             val: *f,
             span: expr.span,
         }),
+        Expr::Binary(val) => Ok(Value::Binary {
+            val: val.clone(),
+            span: expr.span,
+        }),
+        Expr::Nothing => Ok(Value::Nothing { span: expr.span }),
         Expr::Range(from, next, to, operator) => {
             let from = if let Some(f) = from {
                 eval_expression(context, f)?
@@ -153,9 +257,7 @@ pub fn eval_expression(
                 span: expr.span,
             })
         }
-        Expr::Var(var_id) => context
-            .get_var(*var_id)
-            .map_err(move |_| ShellError::VariableNotFoundAtRuntime(expr.span)),
+        Expr::Var(var_id) => eval_variable(context, *var_id, expr.span),
         Expr::FullCellPath(cell_path) => {
             let value = eval_expression(context, &cell_path.head)?;
 
@@ -163,9 +265,15 @@ pub fn eval_expression(
         }
         Expr::RowCondition(_, expr) => eval_expression(context, expr),
         Expr::Call(call) => eval_call(context, call, Value::nothing()),
-        Expr::ExternalCall(name, args) => {
-            eval_external(context, name, args, Value::nothing(), true)
-        }
+        Expr::ExternalCall(head, args, redirections, complete) => eval_external(
+            context,
+            head,
+            args,
+            redirections,
+            Value::nothing(),
+            true,
+            *complete,
+        ),
         Expr::Operator(_) => Ok(Value::Nothing { span: expr.span }),
         Expr::BinaryOp(lhs, op, rhs) => {
             let op_span = op.span;
@@ -231,11 +339,26 @@ pub fn eval_expression(
                 span: expr.span,
             })
         }
+        Expr::Record(fields) => {
+            let mut cols = vec![];
+            let mut vals = vec![];
+            for (col, val) in fields {
+                cols.push(eval_expression(context, col)?.as_string()?);
+                vals.push(eval_expression(context, val)?);
+            }
+
+            Ok(Value::Record {
+                cols,
+                vals,
+                span: expr.span,
+            })
+        }
         Expr::Keyword(_, _, expr) => eval_expression(context, expr),
-        Expr::String(s) => Ok(Value::String {
+        Expr::String(s) | Expr::GlobPattern(s) => Ok(Value::String {
             val: s.clone(),
             span: expr.span,
         }),
+        Expr::ImportPattern(_) => Ok(Value::Nothing { span: expr.span }),
         Expr::Signature(_) => Ok(Value::Nothing { span: expr.span }),
         Expr::Garbage => Ok(Value::Nothing { span: expr.span }),
     }
@@ -257,15 +380,17 @@ pub fn eval_block(
                         input = eval_call(context, call, input)?;
                     }
                     Expression {
-                        expr: Expr::ExternalCall(name, args),
+                        expr: Expr::ExternalCall(head, args, redirections, complete),
                         ..
                     } => {
                         input = eval_external(
                             context,
-                            name,
+                            head,
                             args,
+                            redirections,
                             input,
                             i == pipeline.expressions.len() - 1,
+                            *complete,
                         )?;
                     }
 
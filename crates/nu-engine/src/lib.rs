@@ -1,3 +1,5 @@
+mod env;
 mod eval;
 
-pub use eval::{eval_block, eval_expression, eval_operator};
+pub use env::{current_dir, env_from_string, env_to_string, env_to_strings, lib_dirs, PWD_ENV};
+pub use eval::{eval_block, eval_call, eval_expression, eval_operator};
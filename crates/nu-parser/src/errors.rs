@@ -69,6 +69,26 @@ pub enum ParseError {
     #[diagnostic(code(nu::parser::module_not_found), url(docsrs))]
     ModuleNotFound(#[label = "module not found"] Span),
 
+    #[error("Cyclical module import.")]
+    #[diagnostic(code(nu::parser::cyclical_module_import), url(docsrs))]
+    CyclicalModuleImport(String, #[label("detected cycle when importing {0}")] Span),
+
+    #[error("Cyclical source.")]
+    #[diagnostic(code(nu::parser::cyclical_source), url(docsrs))]
+    CyclicalSource(String, #[label("detected cycle when sourcing {0}")] Span),
+
+    #[error("File not found.")]
+    #[diagnostic(code(nu::parser::file_not_found), url(docsrs))]
+    FileNotFound(String, #[label("File not found: {0}")] Span),
+
+    #[error("Overlay not found.")]
+    #[diagnostic(code(nu::parser::overlay_not_found), url(docsrs))]
+    OverlayNotFound(#[label = "not an active overlay"] Span),
+
+    #[error("Definition not found.")]
+    #[diagnostic(code(nu::parser::definition_not_found), url(docsrs))]
+    DefinitionNotFound(#[label = "not found in the current scope"] Span),
+
     #[error("Unknown command.")]
     #[diagnostic(
         code(nu::parser::unknown_command),
@@ -156,7 +176,19 @@ pub enum ParseError {
     #[diagnostic(code(nu::parser::missing_import_pattern), url(docsrs))]
     MissingImportPattern(#[label = "needs an import pattern"] Span),
 
+    #[error("Statement used outside of a module.")]
+    #[diagnostic(code(nu::parser::unexpected_keyword), url(docsrs))]
+    UnexpectedKeyword(String, #[label("'{0}' keyword is allowed only in a module")] Span),
+
     #[error("Module export not found.")]
     #[diagnostic(code(nu::parser::export_not_found), url(docsrs))]
     ExportNotFound(#[label = "could not find imports"] Span),
+
+    #[error("Redirection of an internal command.")]
+    #[diagnostic(code(nu::parser::redirection_not_external), url(docsrs))]
+    RedirectionNotExternal(#[label = "only external commands can be redirected"] Span),
+
+    #[error("Complete without an external command.")]
+    #[diagnostic(code(nu::parser::complete_not_external), url(docsrs))]
+    CompleteNotExternal(#[label = "only works on external commands"] Span),
 }
@@ -1,4 +1,6 @@
-use nu_protocol::ast::{Block, Expr, Expression, PathMember, Pipeline, Statement};
+use nu_protocol::ast::{
+    Block, Expr, Expression, ExternalArgument, PathMember, Pipeline, Statement,
+};
 use nu_protocol::{engine::StateWorkingSet, Span};
 
 #[derive(Debug)]
@@ -11,6 +13,7 @@ pub enum FlatShape {
     InternalCall,
     External,
     ExternalArg,
+    GlobPattern,
     Literal,
     Operator,
     Signature,
@@ -61,13 +64,34 @@ pub fn flatten_expression(
             }
             output
         }
-        Expr::ExternalCall(name, args) => {
-            let mut output = vec![(*name, FlatShape::External)];
+        Expr::ExternalCall(head, args, redirections, _) => {
+            let mut output = vec![(head.span, FlatShape::External)];
 
             for arg in args {
-                output.push((*arg, FlatShape::ExternalArg));
+                let expr = match arg {
+                    ExternalArgument::Regular(expr) => expr,
+                    ExternalArgument::Spread(operator_span, expr) => {
+                        output.push((*operator_span, FlatShape::Operator));
+                        expr
+                    }
+                };
+
+                match expr.expr {
+                    Expr::String(_) => output.push((expr.span, FlatShape::ExternalArg)),
+                    _ => output.extend(flatten_expression(working_set, expr)),
+                }
             }
 
+            for (_, target) in redirections {
+                match target.expr {
+                    Expr::String(_) => output.push((target.span, FlatShape::ExternalArg)),
+                    _ => output.extend(flatten_expression(working_set, target)),
+                }
+            }
+
+            // Redirections may be written in between the arguments
+            output.sort_by_key(|(span, _)| span.start);
+
             output
         }
         Expr::Garbage => {
@@ -124,12 +148,18 @@ pub fn flatten_expression(
         Expr::Operator(_) => {
             vec![(expr.span, FlatShape::Operator)]
         }
+        Expr::ImportPattern(_) => {
+            vec![(expr.span, FlatShape::String)]
+        }
         Expr::Signature(_) => {
             vec![(expr.span, FlatShape::Signature)]
         }
         Expr::String(_) => {
             vec![(expr.span, FlatShape::String)]
         }
+        Expr::GlobPattern(_) => {
+            vec![(expr.span, FlatShape::GlobPattern)]
+        }
         Expr::RowCondition(_, expr) => flatten_expression(working_set, expr),
         Expr::Subexpression(block_id) => {
             flatten_block(working_set, working_set.get_block(*block_id))
@@ -146,6 +176,17 @@ pub fn flatten_expression(
             }
             output
         }
+        Expr::Record(fields) => {
+            let mut output = vec![];
+            for (key, val) in fields {
+                output.extend(flatten_expression(working_set, key));
+                output.extend(flatten_expression(working_set, val));
+            }
+            output
+        }
+        Expr::Binary(_) | Expr::Nothing => {
+            vec![(expr.span, FlatShape::Literal)]
+        }
         Expr::Var(_) => {
             vec![(expr.span, FlatShape::Variable)]
         }
@@ -8,6 +8,9 @@ pub enum TokenContents {
     Pipe,
     Semicolon,
     Eol,
+    OutGreaterThan,    // `out>`
+    ErrGreaterThan,    // `err>`
+    OutErrGreaterThan, // `out+err>`
 }
 
 #[derive(Debug, PartialEq, Eq)]
@@ -316,8 +319,18 @@ pub fn lex(
             if error.is_none() {
                 error = err;
             }
-            is_complete = true;
-            output.push(Token::new(TokenContents::Item, span));
+
+            // Redirections are only recognized as whole words, `out>file` is a regular item
+            let contents = match &input[(span.start - span_offset)..(span.end - span_offset)] {
+                b"out>" => TokenContents::OutGreaterThan,
+                b"err>" => TokenContents::ErrGreaterThan,
+                b"out+err>" => TokenContents::OutErrGreaterThan,
+                _ => TokenContents::Item,
+            };
+
+            // A redirection still needs the file it points to
+            is_complete = contents == TokenContents::Item;
+            output.push(Token::new(contents, span));
         }
     }
     (output, error)
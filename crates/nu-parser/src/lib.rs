@@ -11,6 +11,7 @@ pub use flatten::{flatten_block, FlatShape};
 pub use lex::{lex, Token, TokenContents};
 pub use lite_parse::{lite_parse, LiteBlock};
 pub use parse_keywords::{
-    parse_alias, parse_def, parse_def_predecl, parse_let, parse_module, parse_use,
+    parse_alias, parse_def, parse_def_predecl, parse_export, parse_hide, parse_let, parse_module,
+    parse_module_block, parse_overlay, parse_source, parse_use, LIB_DIRS_ENV,
 };
 pub use parser::{parse, Import, VarDecl};
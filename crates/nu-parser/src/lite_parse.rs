@@ -1,10 +1,11 @@
 use crate::{ParseError, Token, TokenContents};
-use nu_protocol::Span;
+use nu_protocol::{ast::Redirection, Span};
 
 #[derive(Debug)]
 pub struct LiteCommand {
     pub comments: Vec<Span>,
     pub parts: Vec<Span>,
+    pub redirections: Vec<(Redirection, Span)>,
 }
 
 impl Default for LiteCommand {
@@ -18,6 +19,7 @@ impl LiteCommand {
         Self {
             comments: vec![],
             parts: vec![],
+            redirections: vec![],
         }
     }
 
@@ -85,9 +87,31 @@ pub fn lite_parse(tokens: &[Token]) -> (LiteBlock, Option<ParseError>) {
     let mut curr_pipeline = LiteStatement::new();
     let mut curr_command = LiteCommand::new();
 
+    // A redirection that is still waiting for the file it points to
+    let mut curr_redirection: Option<(Redirection, &Token)> = None;
+    let mut error = None;
+
     for token in tokens.iter() {
+        if let Some((redirection, redirection_token)) = curr_redirection.take() {
+            if token.contents == TokenContents::Item {
+                curr_command.redirections.push((redirection, token.span));
+                continue;
+            }
+
+            error = error.or_else(|| Some(missing_redirection_target(redirection_token)));
+        }
+
         match &token.contents {
             TokenContents::Item => curr_command.push(token.span),
+            TokenContents::OutGreaterThan => {
+                curr_redirection = Some((Redirection::Stdout, token));
+            }
+            TokenContents::ErrGreaterThan => {
+                curr_redirection = Some((Redirection::Stderr, token));
+            }
+            TokenContents::OutErrGreaterThan => {
+                curr_redirection = Some((Redirection::StdoutAndStderr, token));
+            }
             TokenContents::Pipe => {
                 if !curr_command.is_empty() {
                     curr_pipeline.push(curr_command);
@@ -113,6 +137,10 @@ pub fn lite_parse(tokens: &[Token]) -> (LiteBlock, Option<ParseError>) {
         }
     }
 
+    if let Some((_, redirection_token)) = curr_redirection {
+        error = error.or_else(|| Some(missing_redirection_target(redirection_token)));
+    }
+
     if !curr_command.is_empty() {
         curr_pipeline.push(curr_command);
     }
@@ -121,5 +149,15 @@ pub fn lite_parse(tokens: &[Token]) -> (LiteBlock, Option<ParseError>) {
         block.push(curr_pipeline);
     }
 
-    (block, None)
+    (block, error)
+}
+
+fn missing_redirection_target(token: &Token) -> ParseError {
+    let name = match token.contents {
+        TokenContents::ErrGreaterThan => "err>",
+        TokenContents::OutErrGreaterThan => "out+err>",
+        _ => "out>",
+    };
+
+    ParseError::KeywordMissingArgument(name.into(), token.span)
 }
@@ -1,22 +1,27 @@
 use nu_protocol::{
-    ast::{Block, Call, Expr, Expression, ImportPatternMember, Pipeline, Statement},
+    ast::{Block, Call, Expr, Expression, ImportPattern, Pipeline, Statement},
     engine::StateWorkingSet,
-    span, DeclId, Span, SyntaxShape, Type,
+    span, BlockId, Overlay, Span, SyntaxShape, Type,
 };
+use std::path::{Path, PathBuf};
 
 use crate::{
     lex, lite_parse,
     parser::{
-        check_name, garbage, garbage_statement, parse_block_expression, parse_import_pattern,
-        parse_internal_call, parse_signature, parse_string,
+        check_name, garbage, garbage_statement, parse_block, parse_block_expression,
+        parse_expression, parse_import_pattern, parse_import_pattern_member, parse_internal_call,
+        parse_signature, parse_string,
     },
     ParseError,
 };
 
+/// Environment variable with the directories searched for module files
+pub const LIB_DIRS_ENV: &str = "NU_LIB_DIRS";
+
 pub fn parse_def_predecl(working_set: &mut StateWorkingSet, spans: &[Span]) {
     let name = working_set.get_span_contents(spans[0]);
 
-    if name == b"def" && spans.len() >= 4 {
+    if (name == b"def" || name == b"def-env") && spans.len() >= 4 {
         let (name_expr, ..) = parse_string(working_set, spans[1]);
         let name = name_expr.as_string();
 
@@ -35,7 +40,7 @@ pub fn parse_def_predecl(working_set: &mut StateWorkingSet, spans: &[Span]) {
             signature.name = name;
             let decl = signature.predeclare();
 
-            working_set.add_decl(decl);
+            working_set.add_predecl(decl);
         }
     }
 }
@@ -47,9 +52,11 @@ pub fn parse_def(
     let mut error = None;
     let name = working_set.get_span_contents(spans[0]);
 
-    if name == b"def" {
+    if name == b"def" || name == b"def-env" {
+        let redirect_env = name == b"def-env";
+
         let def_decl_id = working_set
-            .find_decl(b"def")
+            .find_decl(name)
             .expect("internal error: missing def command");
 
         let mut call = Box::new(Call {
@@ -67,6 +74,12 @@ pub fn parse_def(
             call.positional.push(name_expr);
 
             if let Some(sig_span) = spans.get(2) {
+                let predecl_id = name
+                    .as_ref()
+                    .and_then(|name| working_set.take_predecl(name.as_bytes()));
+
+                let mut shadowed_decl = None;
+
                 working_set.enter_scope();
                 let (sig, err) = parse_signature(working_set, *sig_span);
                 error = error.or(err);
@@ -85,15 +98,23 @@ pub fn parse_def(
 
                     let block_id = block.as_block();
 
+                    if let (true, Some(block_id)) = (redirect_env, block_id) {
+                        working_set.get_block_mut(block_id).redirect_env = true;
+                    }
+
                     call.positional.push(block);
 
                     if let (Some(name), Some(mut signature), Some(block_id)) =
                         (name, signature, block_id)
                     {
-                        let decl_id = working_set
-                            .find_decl(name.as_bytes())
+                        let decl_id = predecl_id
                             .expect("internal error: predeclaration failed to add definition");
 
+                        // An overlay added since the predeclaration can shadow it
+                        if working_set.find_decl(name.as_bytes()) != Some(decl_id) {
+                            shadowed_decl = Some((name.as_bytes().to_vec(), decl_id));
+                        }
+
                         let declaration = working_set.get_decl_mut(decl_id);
 
                         signature.name = name;
@@ -111,6 +132,10 @@ pub fn parse_def(
                 }
                 working_set.exit_scope();
 
+                if let Some((name, decl_id)) = shadowed_decl {
+                    working_set.insert_decl_name(name, decl_id);
+                }
+
                 call
             } else {
                 let err_span = Span {
@@ -218,6 +243,253 @@ pub fn parse_alias(
     )
 }
 
+pub fn parse_export(
+    working_set: &mut StateWorkingSet,
+    spans: &[Span],
+) -> (Statement, Option<Overlay>, Option<ParseError>) {
+    let bytes = working_set.get_span_contents(spans[0]);
+
+    if bytes != b"export" {
+        return (
+            garbage_statement(spans),
+            None,
+            Some(ParseError::UnknownState(
+                "internal error: Wrong call name for 'export' command".into(),
+                span(spans),
+            )),
+        );
+    }
+
+    let sub = if let Some(sub_span) = spans.get(1) {
+        working_set.get_span_contents(*sub_span).to_vec()
+    } else {
+        return (
+            garbage_statement(spans),
+            None,
+            Some(ParseError::MissingPositional(
+                "def, def-env, alias or env keyword".into(),
+                Span {
+                    start: spans[0].end,
+                    end: spans[0].end,
+                },
+            )),
+        );
+    };
+
+    let mut exports = Overlay::new();
+
+    let (stmt, err) = match sub.as_slice() {
+        b"def" | b"def-env" => {
+            let (stmt, err) = parse_def(working_set, &spans[1..]);
+
+            if err.is_none() {
+                if let Some(name) = spans.get(2).and_then(|name_span| {
+                    let (name_expr, _) = parse_string(working_set, *name_span);
+                    name_expr.as_string()
+                }) {
+                    let decl_id = working_set
+                        .find_decl(name.as_bytes())
+                        .expect("internal error: failed to find added declaration");
+
+                    exports.add_decl(name.as_bytes(), decl_id);
+                }
+            }
+
+            (stmt, err)
+        }
+        b"alias" => {
+            let (stmt, err) = parse_alias(working_set, &spans[1..]);
+
+            if err.is_none() && spans.len() >= 5 {
+                let alias_name = working_set.get_span_contents(spans[2]);
+
+                let alias_name = if alias_name.starts_with(b"\"")
+                    && alias_name.ends_with(b"\"")
+                    && alias_name.len() > 1
+                {
+                    alias_name[1..(alias_name.len() - 1)].to_vec()
+                } else {
+                    alias_name.to_vec()
+                };
+
+                if let Some(replacement) = working_set.find_alias(&alias_name) {
+                    let replacement = replacement.to_vec();
+                    exports.add_alias(&alias_name, replacement);
+                }
+            }
+
+            (stmt, err)
+        }
+        b"env" => {
+            let export_env_decl_id = working_set
+                .find_decl(b"export env")
+                .expect("internal error: missing export env command");
+
+            let (call, _, err) = parse_internal_call(
+                working_set,
+                span(&spans[0..2]),
+                &spans[2..],
+                export_env_decl_id,
+            );
+
+            if err.is_none() {
+                if let (Some(name), Some(block_id)) = (
+                    call.positional.first().and_then(|expr| expr.as_string()),
+                    call.positional.get(1).and_then(|expr| expr.as_block()),
+                ) {
+                    exports.add_env_var(name.as_bytes(), block_id);
+                }
+            }
+
+            (
+                Statement::Pipeline(Pipeline::from_vec(vec![Expression {
+                    expr: Expr::Call(call),
+                    span: span(spans),
+                    ty: Type::Unknown,
+                    custom_completion: None,
+                }])),
+                err,
+            )
+        }
+        _ => {
+            return (
+                garbage_statement(spans),
+                None,
+                Some(ParseError::Expected(
+                    // TODO: Fill in more as they come
+                    "def, def-env, alias or env keyword".into(),
+                    spans[1],
+                )),
+            );
+        }
+    };
+
+    // `export def` and `export alias` are parsed as their plain counterparts, so the call has to
+    // be pointed at the `export` version of the command
+    let stmt = if sub.as_slice() == b"env" {
+        stmt
+    } else {
+        let mut export_name = b"export ".to_vec();
+        export_name.extend(&sub);
+
+        let export_decl_id = working_set
+            .find_decl(&export_name)
+            .expect("internal error: missing export command");
+
+        match stmt {
+            Statement::Pipeline(mut pipeline) => {
+                if let Some(Expression {
+                    expr: Expr::Call(ref mut call),
+                    ref mut span,
+                    ..
+                }) = pipeline.expressions.get_mut(0)
+                {
+                    call.decl_id = export_decl_id;
+                    call.head = nu_protocol::span(&spans[0..2]);
+                    *span = nu_protocol::span(spans);
+                }
+
+                Statement::Pipeline(pipeline)
+            }
+            stmt => stmt,
+        }
+    };
+
+    (stmt, Some(exports), err)
+}
+
+/// Parse the contents of a module (without the surrounding braces), collecting its exports
+pub fn parse_module_block(
+    working_set: &mut StateWorkingSet,
+    span: Span,
+) -> (Block, Option<ParseError>) {
+    let mut error = None;
+
+    let source = working_set.get_span_contents(span);
+
+    let (output, err) = lex(source, span.start, &[], &[]);
+    error = error.or(err);
+
+    working_set.enter_scope();
+
+    // Do we need block parameters?
+
+    let (output, err) = lite_parse(&output);
+    error = error.or(err);
+
+    // We probably don't need $it
+
+    // we're doing parse_block() equivalent
+    // let (mut output, err) = parse_block(working_set, &output, false);
+
+    for pipeline in &output.block {
+        if pipeline.commands.len() == 1 {
+            let parts = &pipeline.commands[0].parts;
+
+            if working_set.get_span_contents(parts[0]) == b"export" {
+                if parts.len() > 1 {
+                    parse_def_predecl(working_set, &parts[1..]);
+                }
+            } else {
+                parse_def_predecl(working_set, parts);
+            }
+        }
+    }
+
+    let mut exports = Overlay::new();
+
+    let block: Block = output
+        .block
+        .iter()
+        .map(|pipeline| {
+            if pipeline.commands.len() == 1 {
+                // this one here is doing parse_statement() equivalent
+                // let (stmt, err) = parse_statement(working_set, &pipeline.commands[0].parts);
+                let name = working_set.get_span_contents(pipeline.commands[0].parts[0]);
+
+                let (stmt, err) = match name {
+                    b"def" | b"def-env" => parse_def(working_set, &pipeline.commands[0].parts),
+                    b"alias" => parse_alias(working_set, &pipeline.commands[0].parts),
+                    b"use" => parse_use(working_set, &pipeline.commands[0].parts),
+                    b"export" => {
+                        let (stmt, exportables, err) =
+                            parse_export(working_set, &pipeline.commands[0].parts);
+
+                        if let Some(exportables) = exportables {
+                            exports.extend(exportables);
+                        }
+
+                        (stmt, err)
+                    }
+                    _ => (
+                        garbage_statement(&pipeline.commands[0].parts),
+                        Some(ParseError::Expected(
+                            // TODO: Fill in more as they come
+                            "def, def-env, alias, use or export".into(),
+                            pipeline.commands[0].parts[0],
+                        )),
+                    ),
+                };
+
+                if error.is_none() {
+                    error = err;
+                }
+
+                stmt
+            } else {
+                error = Some(ParseError::Expected("not a pipeline".into(), span));
+                garbage_statement(&pipeline.commands[0].parts)
+            }
+        })
+        .into();
+
+    let block = block.with_exports(exports);
+
+    working_set.exit_scope();
+
+    (block, error)
+}
+
 pub fn parse_module(
     working_set: &mut StateWorkingSet,
     spans: &[Span],
@@ -268,84 +540,9 @@ pub fn parse_module(
 
         let block_span = Span { start, end };
 
-        let source = working_set.get_span_contents(block_span);
-
-        let (output, err) = lex(source, start, &[], &[]);
-        error = error.or(err);
-
-        working_set.enter_scope();
-
-        // Do we need block parameters?
-
-        let (output, err) = lite_parse(&output);
+        let (block, err) = parse_module_block(working_set, block_span);
         error = error.or(err);
 
-        // We probably don't need $it
-
-        // we're doing parse_block() equivalent
-        // let (mut output, err) = parse_block(working_set, &output, false);
-
-        for pipeline in &output.block {
-            if pipeline.commands.len() == 1 {
-                parse_def_predecl(working_set, &pipeline.commands[0].parts);
-            }
-        }
-
-        let mut exports: Vec<(Vec<u8>, DeclId)> = vec![];
-
-        let block: Block = output
-            .block
-            .iter()
-            .map(|pipeline| {
-                if pipeline.commands.len() == 1 {
-                    // this one here is doing parse_statement() equivalent
-                    // let (stmt, err) = parse_statement(working_set, &pipeline.commands[0].parts);
-                    let name = working_set.get_span_contents(pipeline.commands[0].parts[0]);
-
-                    let (stmt, err) = match name {
-                        // TODO: Here we can add other stuff that's alowed for modules
-                        b"def" => {
-                            let (stmt, err) = parse_def(working_set, &pipeline.commands[0].parts);
-
-                            if err.is_none() {
-                                let decl_name =
-                                    working_set.get_span_contents(pipeline.commands[0].parts[1]);
-
-                                let decl_id = working_set
-                                    .find_decl(decl_name)
-                                    .expect("internal error: failed to find added declaration");
-
-                                // TODO: Later, we want to put this behind 'export'
-                                exports.push((decl_name.into(), decl_id));
-                            }
-
-                            (stmt, err)
-                        }
-                        _ => (
-                            garbage_statement(&pipeline.commands[0].parts),
-                            Some(ParseError::Expected(
-                                "def".into(),
-                                pipeline.commands[0].parts[0],
-                            )),
-                        ),
-                    };
-
-                    if error.is_none() {
-                        error = err;
-                    }
-
-                    stmt
-                } else {
-                    error = Some(ParseError::Expected("not a pipeline".into(), block_span));
-                    garbage_statement(spans)
-                }
-            })
-            .into();
-
-        let block = block.with_exports(exports);
-
-        working_set.exit_scope();
-
         let block_id = working_set.add_module(&module_name, block);
 
         let block_expr = Expression {
@@ -386,6 +583,157 @@ pub fn parse_module(
     }
 }
 
+/// Module files are recognized by their extension, everything else is a module name
+fn is_module_file(working_set: &StateWorkingSet, span: Span) -> bool {
+    let bytes = trim_quotes(working_set.get_span_contents(span));
+
+    bytes.ends_with(b".nu")
+}
+
+fn trim_quotes(bytes: &[u8]) -> &[u8] {
+    if (bytes.starts_with(b"\"") && bytes.ends_with(b"\"") && bytes.len() > 1)
+        || (bytes.starts_with(b"\'") && bytes.ends_with(b"\'") && bytes.len() > 1)
+    {
+        &bytes[1..(bytes.len() - 1)]
+    } else {
+        bytes
+    }
+}
+
+/// Resolve a module or script file path. Relative paths are looked up next to the file containing `span`
+/// first, then in the library directories the working set was given from `NU_LIB_DIRS`.
+fn find_nu_file(working_set: &StateWorkingSet, path: &Path, span: Span) -> Option<PathBuf> {
+    if path.is_absolute() {
+        return path.canonicalize().ok();
+    }
+
+    let current_dir = working_set
+        .get_span_filename(span)
+        .map(PathBuf::from)
+        .filter(|filename| filename.is_file())
+        .and_then(|filename| filename.parent().map(|dir| dir.to_path_buf()))
+        .or_else(|| std::env::current_dir().ok());
+
+    current_dir
+        .into_iter()
+        .chain(working_set.lib_dirs.iter().cloned())
+        .map(|dir| dir.join(path))
+        .find(|candidate| candidate.is_file())
+        .and_then(|candidate| candidate.canonicalize().ok())
+}
+
+/// Parse a module file, returning its name (the file stem) and block. Modules are cached per
+/// canonical path, so each file is parsed only once.
+fn parse_module_file(
+    working_set: &mut StateWorkingSet,
+    span: Span,
+) -> (Option<(Vec<u8>, BlockId)>, Option<ParseError>) {
+    let filename = String::from_utf8_lossy(trim_quotes(working_set.get_span_contents(span)))
+        .to_string();
+
+    let path = if let Some(path) = find_nu_file(working_set, Path::new(&filename), span) {
+        path
+    } else {
+        return (None, Some(ParseError::ModuleNotFound(span)));
+    };
+
+    let name = path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().as_bytes().to_vec())
+        .unwrap_or_default();
+
+    if let Some(block_id) = working_set.find_module_file(&path) {
+        return (Some((name, block_id)), None);
+    }
+
+    if working_set.parsed_files.contains(&path) {
+        return (
+            None,
+            Some(ParseError::CyclicalSource(
+                path.to_string_lossy().to_string(),
+                span,
+            )),
+        );
+    }
+
+    let contents = if let Ok(contents) = std::fs::read(&path) {
+        contents
+    } else {
+        return (None, Some(ParseError::ModuleNotFound(span)));
+    };
+
+    let start = working_set.next_span_start();
+    working_set.add_file(path.to_string_lossy().to_string(), &contents);
+    let end = working_set.next_span_start();
+
+    working_set.parsed_files.push(path.clone());
+    let (block, err) = parse_module_block(working_set, Span { start, end });
+    working_set.parsed_files.pop();
+
+    let block_id = working_set.add_block(block);
+    working_set.add_module_file(path, block_id);
+
+    (Some((name, block_id)), err)
+}
+
+/// Parse a reference to a module: either a module name followed by an import pattern, or a
+/// module file optionally followed by the members to import. The module is looked up (or
+/// parsed, in case of a file) and its block is stored in the returned pattern.
+fn parse_module_reference(
+    working_set: &mut StateWorkingSet,
+    spans: &[Span],
+) -> (Option<ImportPattern>, Option<ParseError>) {
+    let mut error = None;
+
+    if is_module_file(working_set, spans[0]) {
+        let (module, err) = parse_module_file(working_set, spans[0]);
+        error = error.or(err);
+
+        let (head, module_id) = if let Some(module) = module {
+            module
+        } else {
+            return (None, error);
+        };
+
+        let members = if let Some(member_span) = spans.get(1) {
+            let (member, err) = parse_import_pattern_member(working_set, *member_span);
+            error = error.or(err);
+
+            member.into_iter().collect()
+        } else {
+            vec![]
+        };
+
+        (
+            Some(ImportPattern {
+                head,
+                members,
+                module_id: Some(module_id),
+            }),
+            error,
+        )
+    } else {
+        let (mut import_pattern, err) = parse_import_pattern(working_set, spans[0]);
+        error = error.or(err);
+
+        // The members can also follow the name, like `spam [foo bar]`
+        if let (true, Some(member_span)) = (import_pattern.members.is_empty(), spans.get(1)) {
+            let (member, err) = parse_import_pattern_member(working_set, *member_span);
+            error = error.or(err);
+
+            import_pattern.members = member.into_iter().collect();
+        }
+
+        if let Some(module_id) = working_set.find_module(&import_pattern.head) {
+            import_pattern.module_id = Some(module_id);
+
+            (Some(import_pattern), error)
+        } else {
+            (None, Some(ParseError::ModuleNotFound(spans[0])))
+        }
+    }
+}
+
 pub fn parse_use(
     working_set: &mut StateWorkingSet,
     spans: &[Span],
@@ -393,69 +741,29 @@ pub fn parse_use(
     let mut error = None;
     let bytes = working_set.get_span_contents(spans[0]);
 
-    // TODO: Currently, this directly imports the module's definitions into the current scope.
-    // Later, we want to put them behind the module's name and add selective importing
     if bytes == b"use" && spans.len() >= 2 {
-        let (module_name_expr, err) = parse_string(working_set, spans[1]);
-        error = error.or(err);
-
-        let (import_pattern, err) = parse_import_pattern(working_set, spans[1]);
+        let (import_pattern, err) = parse_module_reference(working_set, &spans[1..]);
         error = error.or(err);
 
-        let exports = if let Some(block_id) = working_set.find_module(&import_pattern.head) {
-            // TODO: Since we don't use the Block at all, we might just as well create a separate
-            // Module that holds only the exports, without having Blocks in the way.
-            working_set.get_block(block_id).exports.clone()
+        let import_pattern = if let Some(import_pattern) = import_pattern {
+            import_pattern
         } else {
-            return (
-                garbage_statement(spans),
-                Some(ParseError::ModuleNotFound(spans[1])),
-            );
+            return (garbage_statement(spans), error);
         };
 
-        let exports = if import_pattern.members.is_empty() {
-            exports
-                .into_iter()
-                .map(|(name, id)| {
-                    let mut new_name = import_pattern.head.to_vec();
-                    new_name.push(b'.');
-                    new_name.extend(&name);
-                    (new_name, id)
-                })
-                .collect()
-        } else {
-            match &import_pattern.members[0] {
-                ImportPatternMember::Glob { .. } => exports,
-                ImportPatternMember::Name { name, span } => {
-                    let new_exports: Vec<(Vec<u8>, usize)> =
-                        exports.into_iter().filter(|x| &x.0 == name).collect();
-
-                    if new_exports.is_empty() {
-                        error = error.or(Some(ParseError::ExportNotFound(*span)))
-                    }
+        let module_id = import_pattern
+            .module_id
+            .expect("internal error: module reference without module");
 
-                    new_exports
-                }
-                ImportPatternMember::List { names } => {
-                    let mut output = vec![];
-
-                    for (name, span) in names {
-                        let mut new_exports: Vec<(Vec<u8>, usize)> = exports
-                            .iter()
-                            .filter_map(|x| if &x.0 == name { Some(x.clone()) } else { None })
-                            .collect();
-
-                        if new_exports.is_empty() {
-                            error = error.or(Some(ParseError::ExportNotFound(*span)))
-                        } else {
-                            output.append(&mut new_exports)
-                        }
-                    }
+        // TODO: Since we don't use the Block at all, we might just as well create a separate
+        // Module that holds only the exports, without having Blocks in the way.
+        let exports = working_set.get_block(module_id).exports.clone();
 
-                    output
-                }
-            }
-        };
+        let (exports, missing) = exports.select(&import_pattern);
+
+        if let Some(missing_span) = missing {
+            error = error.or(Some(ParseError::ExportNotFound(missing_span)));
+        }
 
         // Extend the current scope with the module's exports
         working_set.activate_overlay(exports);
@@ -468,7 +776,12 @@ pub fn parse_use(
         let call = Box::new(Call {
             head: spans[0],
             decl_id: use_decl_id,
-            positional: vec![module_name_expr],
+            positional: vec![Expression {
+                expr: Expr::ImportPattern(import_pattern),
+                span: span(&spans[1..]),
+                ty: Type::Unknown,
+                custom_completion: None,
+            }],
             named: vec![],
         });
 
@@ -492,6 +805,441 @@ pub fn parse_use(
     }
 }
 
+pub fn parse_source(
+    working_set: &mut StateWorkingSet,
+    spans: &[Span],
+) -> (Statement, Option<ParseError>) {
+    let mut error = None;
+    let name = working_set.get_span_contents(spans[0]);
+
+    if name == b"source" {
+        if let Some(decl_id) = working_set.find_decl(b"source") {
+            let (mut call, call_span, err) =
+                parse_internal_call(working_set, spans[0], &spans[1..], decl_id);
+            error = error.or(err);
+
+            if error.is_none() {
+                // The file is parsed right away, so its path has to be known at parse time
+                let filename = call.positional.first().and_then(|expr| expr.as_string());
+                let path_span = span(&spans[1..]);
+
+                if let Some(filename) = filename {
+                    let (block, err) = parse_source_file(working_set, &filename, path_span);
+                    error = error.or(err);
+
+                    // The block of the file gets the span of the file, where its contents are
+                    if let Some((block_id, block_span)) = block {
+                        call.positional.push(Expression {
+                            expr: Expr::Block(block_id),
+                            span: block_span,
+                            ty: Type::Block,
+                            custom_completion: None,
+                        });
+                    }
+                } else {
+                    error = Some(ParseError::Expected("constant file path".into(), path_span));
+                }
+            }
+
+            return (
+                Statement::Pipeline(Pipeline::from_vec(vec![Expression {
+                    expr: Expr::Call(call),
+                    span: call_span,
+                    ty: Type::Unknown,
+                    custom_completion: None,
+                }])),
+                error,
+            );
+        }
+    }
+
+    (
+        garbage_statement(spans),
+        Some(ParseError::UnknownState(
+            "internal error: source statement unparseable".into(),
+            span(spans),
+        )),
+    )
+}
+
+/// Parse a script file into the current scope, so that its definitions and variables stay
+/// visible after it's sourced. Returns the block along with the span of the file's contents.
+fn parse_source_file(
+    working_set: &mut StateWorkingSet,
+    filename: &str,
+    span: Span,
+) -> (Option<(BlockId, Span)>, Option<ParseError>) {
+    let path = if let Some(path) = find_nu_file(working_set, Path::new(filename), span) {
+        path
+    } else {
+        return (None, Some(ParseError::FileNotFound(filename.into(), span)));
+    };
+
+    if working_set.parsed_files.contains(&path) {
+        return (
+            None,
+            Some(ParseError::CyclicalSource(
+                path.to_string_lossy().to_string(),
+                span,
+            )),
+        );
+    }
+
+    let contents = if let Ok(contents) = std::fs::read(&path) {
+        contents
+    } else {
+        return (None, Some(ParseError::FileNotFound(filename.into(), span)));
+    };
+
+    let start = working_set.next_span_start();
+    working_set.add_file(path.to_string_lossy().to_string(), &contents);
+
+    let mut error = None;
+
+    let (output, err) = lex(&contents, start, &[], &[]);
+    error = error.or(err);
+
+    let (output, err) = lite_parse(&output);
+    error = error.or(err);
+
+    working_set.parsed_files.push(path);
+    let (block, err) = parse_block(working_set, &output, false);
+    error = error.or(err);
+    working_set.parsed_files.pop();
+
+    let block_id = working_set.add_block(block);
+
+    (
+        Some((
+            block_id,
+            Span {
+                start,
+                end: start + contents.len(),
+            },
+        )),
+        error,
+    )
+}
+
+pub fn parse_overlay(
+    working_set: &mut StateWorkingSet,
+    spans: &[Span],
+) -> (Statement, Option<ParseError>) {
+    let bytes = working_set.get_span_contents(spans[0]);
+
+    if bytes != b"overlay" {
+        return (
+            garbage_statement(spans),
+            Some(ParseError::UnknownState(
+                "internal error: Wrong call name for 'overlay' command".into(),
+                span(spans),
+            )),
+        );
+    }
+
+    let subcommand = spans
+        .get(1)
+        .map(|sub_span| working_set.get_span_contents(*sub_span).to_vec());
+
+    // Only adding and removing overlays changes the scope, the rest are regular commands
+    match subcommand.as_deref() {
+        Some(b"add") => parse_overlay_add(working_set, spans),
+        Some(b"remove") => parse_overlay_remove(working_set, spans),
+        _ => {
+            let (expr, err) = parse_expression(working_set, spans);
+            (Statement::Pipeline(Pipeline::from_vec(vec![expr])), err)
+        }
+    }
+}
+
+fn parse_overlay_add(
+    working_set: &mut StateWorkingSet,
+    spans: &[Span],
+) -> (Statement, Option<ParseError>) {
+    let mut error = None;
+
+    if spans.len() < 3 {
+        return (
+            garbage_statement(spans),
+            Some(ParseError::MissingPositional(
+                "module name".into(),
+                Span {
+                    start: spans[1].end,
+                    end: spans[1].end,
+                },
+            )),
+        );
+    }
+
+    if spans.len() > 3 {
+        return (
+            garbage_statement(spans),
+            Some(ParseError::ExtraPositional(span(&spans[3..]))),
+        );
+    }
+
+    let (import_pattern, err) = parse_module_reference(working_set, &spans[2..]);
+    error = error.or(err);
+
+    let import_pattern = if let Some(import_pattern) = import_pattern {
+        import_pattern
+    } else {
+        return (garbage_statement(spans), error);
+    };
+
+    // The whole module becomes the overlay, picking only some of its members is not supported
+    if !import_pattern.members.is_empty() {
+        return (
+            garbage_statement(spans),
+            Some(ParseError::Expected("module name".into(), spans[2])),
+        );
+    }
+
+    let module_id = import_pattern
+        .module_id
+        .expect("internal error: module reference without module");
+
+    let exports = working_set.get_block(module_id).exports.clone();
+    working_set.add_overlay(&import_pattern.head, exports);
+
+    let overlay_add_decl_id = working_set
+        .find_decl(b"overlay add")
+        .expect("internal error: missing overlay add command");
+
+    let call = Box::new(Call {
+        head: span(&spans[0..2]),
+        decl_id: overlay_add_decl_id,
+        positional: vec![Expression {
+            expr: Expr::ImportPattern(import_pattern),
+            span: spans[2],
+            ty: Type::Unknown,
+            custom_completion: None,
+        }],
+        named: vec![],
+    });
+
+    (
+        Statement::Pipeline(Pipeline::from_vec(vec![Expression {
+            expr: Expr::Call(call),
+            span: span(spans),
+            ty: Type::Unknown,
+            custom_completion: None,
+        }])),
+        error,
+    )
+}
+
+fn parse_overlay_remove(
+    working_set: &mut StateWorkingSet,
+    spans: &[Span],
+) -> (Statement, Option<ParseError>) {
+    let mut error = None;
+
+    if spans.len() > 3 {
+        return (
+            garbage_statement(spans),
+            Some(ParseError::ExtraPositional(span(&spans[3..]))),
+        );
+    }
+
+    let (name_expr, name) = if let Some(name_span) = spans.get(2) {
+        let (name_expr, err) = parse_string(working_set, *name_span);
+        error = error.or(err);
+
+        let name = name_expr.as_string().unwrap_or_default().into_bytes();
+
+        (name_expr, name)
+    } else if let Some(name) = working_set.active_overlays().pop() {
+        // Without a name, the most recently added overlay is removed
+        let name_expr = Expression {
+            expr: Expr::String(String::from_utf8_lossy(&name).to_string()),
+            span: spans[1],
+            ty: Type::String,
+            custom_completion: None,
+        };
+
+        (name_expr, name)
+    } else {
+        return (
+            garbage_statement(spans),
+            Some(ParseError::OverlayNotFound(span(spans))),
+        );
+    };
+
+    if !working_set.remove_overlay(&name) {
+        return (
+            garbage_statement(spans),
+            Some(ParseError::OverlayNotFound(name_expr.span)),
+        );
+    }
+
+    let overlay_remove_decl_id = working_set
+        .find_decl(b"overlay remove")
+        .expect("internal error: missing overlay remove command");
+
+    let call = Box::new(Call {
+        head: span(&spans[0..2]),
+        decl_id: overlay_remove_decl_id,
+        positional: vec![name_expr],
+        named: vec![],
+    });
+
+    (
+        Statement::Pipeline(Pipeline::from_vec(vec![Expression {
+            expr: Expr::Call(call),
+            span: span(spans),
+            ty: Type::Unknown,
+            custom_completion: None,
+        }])),
+        error,
+    )
+}
+
+pub fn parse_hide(
+    working_set: &mut StateWorkingSet,
+    spans: &[Span],
+) -> (Statement, Option<ParseError>) {
+    let mut error = None;
+    let bytes = working_set.get_span_contents(spans[0]);
+
+    if bytes != b"hide" || spans.len() < 2 {
+        return (
+            garbage_statement(spans),
+            Some(ParseError::UnknownState(
+                "Expected structure: hide <name>".into(),
+                span(spans),
+            )),
+        );
+    }
+
+    // Environment variables only exist at runtime, so hiding them is up to the command
+    if working_set.get_span_contents(spans[1]) == b"env" && spans.len() == 3 {
+        let hide_env_decl_id = working_set
+            .find_decl(b"hide env")
+            .expect("internal error: missing hide env command");
+
+        let (call, _, err) = parse_internal_call(
+            working_set,
+            span(&spans[0..2]),
+            &spans[2..],
+            hide_env_decl_id,
+        );
+
+        return (
+            Statement::Pipeline(Pipeline::from_vec(vec![Expression {
+                expr: Expr::Call(call),
+                span: span(spans),
+                ty: Type::Unknown,
+                custom_completion: None,
+            }])),
+            err,
+        );
+    }
+
+    let name = working_set.get_span_contents(spans[1]).to_vec();
+
+    let pattern_expr = if name.starts_with(b"$") {
+        if let Some(var_id) = working_set.find_variable(&name) {
+            working_set.hide_variable(var_id);
+        } else {
+            error = error.or(Some(ParseError::VariableNotFound(spans[1])));
+        }
+
+        Expression {
+            expr: Expr::String(String::from_utf8_lossy(&name).to_string()),
+            span: spans[1],
+            ty: Type::String,
+            custom_completion: None,
+        }
+    } else if spans.len() == 2 && working_set.find_alias(&name).is_some() {
+        working_set.hide_alias(&name);
+
+        Expression {
+            expr: Expr::String(String::from_utf8_lossy(&name).to_string()),
+            span: spans[1],
+            ty: Type::String,
+            custom_completion: None,
+        }
+    } else if let (2, Some(decl_id)) = (spans.len(), working_set.find_decl(&name)) {
+        working_set.hide_decl(decl_id);
+
+        Expression {
+            expr: Expr::String(String::from_utf8_lossy(&name).to_string()),
+            span: spans[1],
+            ty: Type::String,
+            custom_completion: None,
+        }
+    } else {
+        // Not a single definition, so it must refer to (members of) a module
+        let (import_pattern, err) = parse_module_reference(working_set, &spans[1..]);
+
+        let import_pattern = if let Some(import_pattern) = import_pattern {
+            error = error.or(err);
+            import_pattern
+        } else {
+            let err = match err {
+                Some(ParseError::ModuleNotFound(span)) => ParseError::DefinitionNotFound(span),
+                err => err.unwrap_or(ParseError::DefinitionNotFound(spans[1])),
+            };
+
+            return (garbage_statement(spans), Some(err));
+        };
+
+        let module_id = import_pattern
+            .module_id
+            .expect("internal error: module reference without module");
+
+        let (exports, missing) = working_set
+            .get_block(module_id)
+            .exports
+            .select(&import_pattern);
+
+        if let Some(missing_span) = missing {
+            error = error.or(Some(ParseError::ExportNotFound(missing_span)));
+        }
+
+        for (name, decl_id) in exports.decls {
+            if working_set.find_decl(&name) == Some(decl_id) {
+                working_set.hide_decl(decl_id);
+            }
+        }
+
+        for (name, replacement) in exports.aliases {
+            if working_set.find_alias(&name) == Some(&replacement) {
+                working_set.hide_alias(&name);
+            }
+        }
+
+        Expression {
+            expr: Expr::ImportPattern(import_pattern),
+            span: span(&spans[1..]),
+            ty: Type::Unknown,
+            custom_completion: None,
+        }
+    };
+
+    let hide_decl_id = working_set
+        .find_decl(b"hide")
+        .expect("internal error: missing hide command");
+
+    let call = Box::new(Call {
+        head: spans[0],
+        decl_id: hide_decl_id,
+        positional: vec![pattern_expr],
+        named: vec![],
+    });
+
+    (
+        Statement::Pipeline(Pipeline::from_vec(vec![Expression {
+            expr: Expr::Call(call),
+            span: span(spans),
+            ty: Type::Unknown,
+            custom_completion: None,
+        }])),
+        error,
+    )
+}
+
 pub fn parse_let(
     working_set: &mut StateWorkingSet,
     spans: &[Span],
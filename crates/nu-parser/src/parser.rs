@@ -6,15 +6,17 @@ use crate::{
 
 use nu_protocol::{
     ast::{
-        Block, Call, Expr, Expression, FullCellPath, ImportPattern, ImportPatternMember, Operator,
-        PathMember, Pipeline, RangeInclusion, RangeOperator, Statement,
+        Block, Call, Expr, Expression, ExternalArgument, FullCellPath, ImportPattern,
+        ImportPatternMember, Operator, PathMember, Pipeline, RangeInclusion, RangeOperator,
+        Redirection, Statement,
     },
-    engine::StateWorkingSet,
+    engine::{StateWorkingSet, ENV_VARIABLE_ID},
     span, Flag, PositionalArg, Signature, Span, SyntaxShape, Type, VarId,
 };
 
 use crate::parse_keywords::{
-    parse_alias, parse_def, parse_def_predecl, parse_let, parse_module, parse_use,
+    parse_alias, parse_def, parse_def_predecl, parse_hide, parse_let, parse_module, parse_overlay,
+    parse_source, parse_use,
 };
 
 #[derive(Debug, Clone)]
@@ -103,26 +105,195 @@ pub fn check_name<'a>(
 }
 
 pub fn parse_external_call(
-    _working_set: &mut StateWorkingSet,
+    working_set: &mut StateWorkingSet,
     spans: &[Span],
 ) -> (Expression, Option<ParseError>) {
-    // TODO: add external parsing
+    let mut error = None;
+
+    // `^name` always refers to an external command, the caret itself is not part of the name
+    let head_span = if working_set.get_span_contents(spans[0]).starts_with(b"^") {
+        Span {
+            start: spans[0].start + 1,
+            end: spans[0].end,
+        }
+    } else {
+        spans[0]
+    };
+
+    let head_span = unquote_backticks(working_set, head_span).unwrap_or(head_span);
+
+    let (head, err) = parse_string(working_set, head_span);
+    error = error.or(err);
+
     let mut args = vec![];
-    let name = spans[0];
     for span in &spans[1..] {
-        args.push(*span);
+        let (arg, err) = parse_external_arg(working_set, *span);
+        error = error.or(err);
+
+        args.push(arg);
     }
+
     (
         Expression {
-            expr: Expr::ExternalCall(name, args),
+            expr: Expr::ExternalCall(Box::new(head), args, vec![], false),
             span: span(spans),
             ty: Type::Unknown,
             custom_completion: None,
         },
-        None,
+        error,
     )
 }
 
+fn parse_external_arg(
+    working_set: &mut StateWorkingSet,
+    span: Span,
+) -> (ExternalArgument, Option<ParseError>) {
+    let contents = working_set.get_span_contents(span);
+
+    if contents.starts_with(b"...") && matches!(contents.get(3), Some(b'$' | b'(' | b'[')) {
+        let operator_span = Span {
+            start: span.start,
+            end: span.start + 3,
+        };
+
+        let (expr, err) = parse_value(
+            working_set,
+            Span {
+                start: operator_span.end,
+                end: span.end,
+            },
+            &SyntaxShape::Any,
+        );
+
+        (ExternalArgument::Spread(operator_span, expr), err)
+    } else if matches!(contents.first(), Some(b'$' | b'(' | b'[')) {
+        let (expr, err) = parse_value(working_set, span, &SyntaxShape::Any);
+
+        (ExternalArgument::Regular(expr), err)
+    } else if matches!(contents.first(), Some(b'"' | b'\'')) {
+        let (expr, err) = parse_string(working_set, span);
+
+        (ExternalArgument::Regular(expr), err)
+    } else if let Some(inner) = unquote_backticks(working_set, span) {
+        // Backticks quote an argument as it is, without glob expansion
+        let contents = String::from_utf8_lossy(working_set.get_span_contents(inner)).to_string();
+
+        (
+            ExternalArgument::Regular(Expression {
+                expr: Expr::String(contents),
+                span,
+                ty: Type::String,
+                custom_completion: None,
+            }),
+            None,
+        )
+    } else {
+        // Bare words are passed as they are, unless they are globs for nu to expand
+        let contents = String::from_utf8_lossy(contents).to_string();
+
+        let expr = if is_glob(&contents) {
+            Expr::GlobPattern(contents)
+        } else {
+            Expr::String(contents)
+        };
+
+        (
+            ExternalArgument::Regular(Expression {
+                expr,
+                span,
+                ty: Type::String,
+                custom_completion: None,
+            }),
+            None,
+        )
+    }
+}
+
+/// A bare word is a glob if it has a wildcard or a `[...]` class, and no quotes in it
+fn is_glob(word: &str) -> bool {
+    if word.contains(['"', '\'', '`']) {
+        return false;
+    }
+
+    let has_class = match word.find('[') {
+        Some(start) => word[start + 1..].contains(']'),
+        None => false,
+    };
+
+    has_class || word.contains(['*', '?'])
+}
+
+/// The span inside the backticks, if the span is a backtick-quoted string
+fn unquote_backticks(working_set: &StateWorkingSet, span: Span) -> Option<Span> {
+    let contents = working_set.get_span_contents(span);
+
+    if contents.len() > 1 && contents.starts_with(b"`") && contents.ends_with(b"`") {
+        Some(Span {
+            start: span.start + 1,
+            end: span.end - 1,
+        })
+    } else {
+        None
+    }
+}
+
+/// Mark the externals piped into `complete`, which can only follow an external command
+fn parse_complete(
+    working_set: &StateWorkingSet,
+    mut expressions: Vec<Expression>,
+) -> (Vec<Expression>, Option<ParseError>) {
+    let mut error = None;
+
+    let complete_decl_id = match working_set.find_decl(b"complete") {
+        Some(decl_id) => decl_id,
+        None => return (expressions, None),
+    };
+
+    for i in 0..expressions.len() {
+        let head = match &expressions[i].expr {
+            Expr::Call(call) if call.decl_id == complete_decl_id => call.head,
+            _ => continue,
+        };
+
+        match i.checked_sub(1).map(|prev| &mut expressions[prev].expr) {
+            Some(Expr::ExternalCall(_, _, _, complete)) => *complete = true,
+            _ => error = error.or(Some(ParseError::CompleteNotExternal(head))),
+        }
+    }
+
+    (expressions, error)
+}
+
+/// Attach the redirections of a command to it, they can only be used by external commands
+fn parse_redirections(
+    working_set: &mut StateWorkingSet,
+    mut expr: Expression,
+    redirections: &[(Redirection, Span)],
+) -> (Expression, Option<ParseError>) {
+    let mut error = None;
+
+    if let Expr::ExternalCall(_, _, expr_redirections, _) = &mut expr.expr {
+        for (redirection, span) in redirections {
+            let target = match parse_external_arg(working_set, *span) {
+                (ExternalArgument::Regular(target), err) => {
+                    error = error.or(err);
+                    target
+                }
+                (ExternalArgument::Spread(_, target), _) => {
+                    error = error.or(Some(ParseError::Expected("file name".into(), *span)));
+                    target
+                }
+            };
+
+            expr_redirections.push((*redirection, target));
+        }
+    } else if let Some((_, span)) = redirections.first() {
+        error = Some(ParseError::RedirectionNotExternal(*span));
+    }
+
+    (expr, error)
+}
+
 fn parse_long_flag(
     working_set: &mut StateWorkingSet,
     spans: &[Span],
@@ -134,7 +305,7 @@ fn parse_long_flag(
 
     if arg_contents.starts_with(b"--") {
         // FIXME: only use the first you find
-        let split: Vec<_> = arg_contents.split(|x| *x == b'=').collect();
+        let split: Vec<_> = arg_contents[2..].split(|x| *x == b'=').collect();
         let long_name = String::from_utf8(split[0].into());
         if let Ok(long_name) = long_name {
             if let Some(flag) = sig.get_long_flag(&long_name) {
@@ -142,7 +313,7 @@ fn parse_long_flag(
                     if split.len() > 1 {
                         // and we also have the argument
                         let mut span = arg_span;
-                        span.start += long_name.len() + 1; //offset by long flag and '='
+                        span.start += long_name.len() + 3; //offset by '--', long flag and '='
                         let (arg, err) = parse_value(working_set, span, arg_shape);
 
                         (Some(long_name), Some(arg), err)
@@ -168,7 +339,7 @@ fn parse_long_flag(
                     None,
                     Some(ParseError::UnknownFlag(
                         sig.name.clone(),
-                        long_name.clone(),
+                        format!("--{}", long_name),
                         arg_span,
                     )),
                 )
@@ -364,6 +535,27 @@ fn parse_multispan_value(
 
             (arg, error)
         }
+        SyntaxShape::OneOf(shapes) => {
+            // Shapes can take more than one span, so every shape starts over at the same span
+            let start_idx = *spans_idx;
+            let mut first_error = None;
+
+            for shape in shapes {
+                *spans_idx = start_idx;
+
+                match parse_multispan_value(working_set, spans, spans_idx, shape) {
+                    (expr, None) => return (expr, None),
+                    (_, err) => first_error = first_error.or(err),
+                }
+            }
+
+            *spans_idx = start_idx;
+            (
+                garbage(spans[start_idx]),
+                first_error
+                    .or_else(|| Some(ParseError::Expected("value".into(), spans[start_idx]))),
+            )
+        }
         SyntaxShape::Keyword(keyword, arg) => {
             let arg_span = spans[*spans_idx];
 
@@ -572,6 +764,10 @@ pub fn parse_call(
 
     let name = working_set.get_span_contents(spans[pos]);
 
+    if name.starts_with(b"^") {
+        return parse_external_call(working_set, &spans[pos..]);
+    }
+
     let cmd_start = pos;
 
     if expand_aliases {
@@ -818,6 +1014,55 @@ pub fn parse_number(token: &[u8], span: Span) -> (Expression, Option<ParseError>
     }
 }
 
+/// A binary literal like `0x[1f ff]`, the hex digits may be separated by whitespace
+pub fn parse_binary(token: &[u8], span: Span) -> (Expression, Option<ParseError>) {
+    let digits = match token
+        .strip_prefix(b"0x[")
+        .and_then(|token| token.strip_suffix(b"]"))
+    {
+        Some(digits) => digits,
+        None => {
+            return (
+                garbage(span),
+                Some(ParseError::Expected("binary".into(), span)),
+            )
+        }
+    };
+
+    let digits: Vec<u8> = digits
+        .iter()
+        .copied()
+        .filter(|b| !b.is_ascii_whitespace())
+        .collect();
+
+    let mut val = vec![];
+    for pair in digits.chunks(2) {
+        match std::str::from_utf8(pair)
+            .ok()
+            .filter(|pair| pair.len() == 2)
+            .and_then(|pair| u8::from_str_radix(pair, 16).ok())
+        {
+            Some(byte) => val.push(byte),
+            None => {
+                return (
+                    garbage(span),
+                    Some(ParseError::Expected("pairs of hex digits".into(), span)),
+                )
+            }
+        }
+    }
+
+    (
+        Expression {
+            expr: Expr::Binary(val),
+            span,
+            ty: Type::Binary,
+            custom_completion: None,
+        },
+        None,
+    )
+}
+
 pub fn parse_range(
     working_set: &mut StateWorkingSet,
     span: Span,
@@ -1134,6 +1379,26 @@ pub fn parse_variable_expr(
             },
             None,
         );
+    } else if contents == b"$nothing" {
+        return (
+            Expression {
+                expr: Expr::Nothing,
+                span,
+                ty: Type::Nothing,
+                custom_completion: None,
+            },
+            None,
+        );
+    } else if contents == b"$env" {
+        return (
+            Expression {
+                expr: Expr::Var(ENV_VARIABLE_ID),
+                span,
+                ty: Type::Unknown,
+                custom_completion: None,
+            },
+            None,
+        );
     }
 
     let (id, err) = parse_variable(working_set, span);
@@ -1390,6 +1655,7 @@ pub fn parse_import_pattern(
             ImportPattern {
                 head: vec![],
                 members: vec![],
+                module_id: None,
             },
             Some(ParseError::MissingImportPattern(span)),
         );
@@ -1399,66 +1665,63 @@ pub fn parse_import_pattern(
 
     if let Some(tail) = tokens.get(2) {
         // FIXME: expand this to handle deeper imports once we support module imports
-        let tail_span = tail.span;
-        let tail = working_set.get_span_contents(tail.span);
-        if tail == b"*" {
-            (
-                ImportPattern {
-                    head,
-                    members: vec![ImportPatternMember::Glob { span: tail_span }],
-                },
-                error,
-            )
-        } else if tail.starts_with(b"[") {
-            let (result, err) = parse_list_expression(working_set, tail_span, &SyntaxShape::String);
-            error = error.or(err);
+        let (member, err) = parse_import_pattern_member(working_set, tail.span);
+        error = error.or(err);
 
-            let mut output = vec![];
+        (
+            ImportPattern {
+                head,
+                members: member.into_iter().collect(),
+                module_id: None,
+            },
+            error,
+        )
+    } else {
+        (
+            ImportPattern {
+                head,
+                members: vec![],
+                module_id: None,
+            },
+            None,
+        )
+    }
+}
 
-            match result {
-                Expression {
-                    expr: Expr::List(list),
-                    ..
-                } => {
-                    for l in list {
-                        let contents = working_set.get_span_contents(l.span);
-                        output.push((contents.to_vec(), l.span));
-                    }
+/// Parse the member part of an import pattern: `*`, a name, or a list of names
+pub fn parse_import_pattern_member(
+    working_set: &mut StateWorkingSet,
+    span: Span,
+) -> (Option<ImportPatternMember>, Option<ParseError>) {
+    let contents = working_set.get_span_contents(span);
 
-                    (
-                        ImportPattern {
-                            head,
-                            members: vec![ImportPatternMember::List { names: output }],
-                        },
-                        error,
-                    )
+    if contents == b"*" {
+        (Some(ImportPatternMember::Glob { span }), None)
+    } else if contents.starts_with(b"[") {
+        let (result, err) = parse_list_expression(working_set, span, &SyntaxShape::String);
+
+        match result {
+            Expression {
+                expr: Expr::List(list),
+                ..
+            } => {
+                let mut output = vec![];
+
+                for l in list {
+                    let contents = working_set.get_span_contents(l.span);
+                    output.push((contents.to_vec(), l.span));
                 }
-                _ => (
-                    ImportPattern {
-                        head,
-                        members: vec![],
-                    },
-                    Some(ParseError::ExportNotFound(result.span)),
-                ),
+
+                (Some(ImportPatternMember::List { names: output }), err)
             }
-        } else {
-            (
-                ImportPattern {
-                    head,
-                    members: vec![ImportPatternMember::Name {
-                        name: tail.to_vec(),
-                        span: tail_span,
-                    }],
-                },
-                error,
-            )
+            _ => (None, Some(ParseError::ExportNotFound(result.span))),
         }
     } else {
         (
-            ImportPattern {
-                head,
-                members: vec![],
-            },
+            Some(ImportPatternMember::Name {
+                name: contents.to_vec(),
+                span,
+            }),
             None,
         )
     }
@@ -1646,7 +1909,7 @@ pub fn parse_signature_helper(
                                 let flags: Vec<_> =
                                     contents.split(|x| x == &b'(').map(|x| x.to_vec()).collect();
 
-                                let long = String::from_utf8_lossy(&flags[0]).to_string();
+                                let long = String::from_utf8_lossy(&flags[0][2..]).to_string();
                                 let variable_name = flags[0][2..].to_vec();
                                 let var_id = working_set.add_variable(variable_name, Type::Unknown);
 
@@ -1675,7 +1938,7 @@ pub fn parse_signature_helper(
                                     let short_flag =
                                         String::from_utf8_lossy(short_flag).to_string();
                                     let chars: Vec<char> = short_flag.chars().collect();
-                                    let long = String::from_utf8_lossy(&flags[0]).to_string();
+                                    let long = String::from_utf8_lossy(&flags[0][2..]).to_string();
                                     let variable_name = flags[0][2..].to_vec();
                                     let var_id =
                                         working_set.add_variable(variable_name, Type::Unknown);
@@ -2111,6 +2374,126 @@ pub fn parse_table_expression(
     }
 }
 
+// Record fields are separated by commas or newlines, and a key is followed by a `:`
+fn lex_record(working_set: &StateWorkingSet, span: Span) -> (Vec<Token>, Option<ParseError>) {
+    let bytes = working_set.get_span_contents(span);
+
+    let start = span.start + 1;
+    let end = if bytes.ends_with(b"}") && bytes.len() > 1 {
+        span.end - 1
+    } else {
+        span.end
+    };
+
+    let source = working_set.get_span_contents(Span { start, end });
+
+    let (tokens, err) = lex(source, start, b"\n\r,", b":");
+    let tokens = tokens
+        .into_iter()
+        .filter(|token| token.contents == TokenContents::Item)
+        .collect();
+
+    (tokens, err)
+}
+
+/// Where a block isn't expected, a `{` starts a record instead of a block when its first item is
+/// followed by a `:`. An empty `{}` is an empty record.
+fn is_record(working_set: &StateWorkingSet, span: Span) -> bool {
+    let (tokens, _) = lex_record(working_set, span);
+
+    match tokens.get(1) {
+        Some(token) => working_set.get_span_contents(token.span) == b":",
+        None => tokens.is_empty(),
+    }
+}
+
+pub fn parse_record(
+    working_set: &mut StateWorkingSet,
+    span: Span,
+) -> (Expression, Option<ParseError>) {
+    let bytes = working_set.get_span_contents(span);
+    let mut error = None;
+
+    if !bytes.ends_with(b"}") || bytes.len() < 2 {
+        error = error.or_else(|| {
+            Some(ParseError::Unclosed(
+                "}".into(),
+                Span {
+                    start: span.end,
+                    end: span.end + 1,
+                },
+            ))
+        });
+    }
+
+    let (tokens, err) = lex_record(working_set, span);
+    error = error.or(err);
+
+    let mut fields = vec![];
+    let mut cols = vec![];
+    let mut types = vec![];
+
+    let mut idx = 0;
+    while idx < tokens.len() {
+        let (key, err) = parse_value(working_set, tokens[idx].span, &SyntaxShape::String);
+        error = error.or(err);
+
+        match tokens.get(idx + 1) {
+            Some(token) if working_set.get_span_contents(token.span) == b":" => {}
+            Some(token) => {
+                error = error.or_else(|| Some(ParseError::Expected(":".into(), token.span)));
+            }
+            None => {
+                error = error.or_else(|| {
+                    Some(ParseError::Expected(
+                        ":".into(),
+                        Span {
+                            start: tokens[idx].span.end,
+                            end: tokens[idx].span.end,
+                        },
+                    ))
+                });
+            }
+        }
+
+        let val = match tokens.get(idx + 2) {
+            Some(token) => {
+                let (val, err) = parse_value(working_set, token.span, &SyntaxShape::Any);
+                error = error.or(err);
+                val
+            }
+            None => {
+                let end = tokens[tokens.len() - 1].span.end;
+                error = error.or_else(|| {
+                    Some(ParseError::Expected(
+                        "record value".into(),
+                        Span { start: end, end },
+                    ))
+                });
+                garbage(Span { start: end, end })
+            }
+        };
+
+        if let Expr::String(col) = &key.expr {
+            cols.push(col.clone());
+            types.push(val.ty.clone());
+        }
+        fields.push((key, val));
+
+        idx += 3;
+    }
+
+    (
+        Expression {
+            expr: Expr::Record(fields),
+            span,
+            ty: Type::Record(cols, types),
+            custom_completion: None,
+        },
+        error,
+    )
+}
+
 pub fn parse_block_expression(
     working_set: &mut StateWorkingSet,
     shape: &SyntaxShape,
@@ -2257,6 +2640,22 @@ pub fn parse_value(
     span: Span,
     shape: &SyntaxShape,
 ) -> (Expression, Option<ParseError>) {
+    if let SyntaxShape::OneOf(shapes) = shape {
+        let mut first_error = None;
+
+        for shape in shapes {
+            match parse_value(working_set, span, shape) {
+                (expr, None) => return (expr, None),
+                (_, err) => first_error = first_error.or(err),
+            }
+        }
+
+        return (
+            garbage(span),
+            first_error.or_else(|| Some(ParseError::Expected("value".into(), span))),
+        );
+    }
+
     let bytes = working_set.get_span_contents(span);
 
     // First, check the special-cases. These will likely represent specific values as expressions
@@ -2275,8 +2674,12 @@ pub fn parse_value(
         } else {
             return parse_full_cell_path(working_set, None, span);
         }
+    } else if bytes.starts_with(b"0x[") && matches!(shape, SyntaxShape::Any) {
+        return parse_binary(bytes, span);
     } else if bytes.starts_with(b"{") {
-        if matches!(shape, SyntaxShape::Block(_)) || matches!(shape, SyntaxShape::Any) {
+        if !matches!(shape, SyntaxShape::Block(_)) && is_record(working_set, span) {
+            return parse_record(working_set, span);
+        } else if matches!(shape, SyntaxShape::Block(_)) || matches!(shape, SyntaxShape::Any) {
             return parse_block_expression(working_set, shape, span);
         } else {
             return (
@@ -2580,11 +2983,18 @@ pub fn parse_statement(
     let name = working_set.get_span_contents(spans[0]);
 
     match name {
-        b"def" => parse_def(working_set, spans),
+        b"def" | b"def-env" => parse_def(working_set, spans),
         b"let" => parse_let(working_set, spans),
         b"alias" => parse_alias(working_set, spans),
         b"module" => parse_module(working_set, spans),
         b"use" => parse_use(working_set, spans),
+        b"source" => parse_source(working_set, spans),
+        b"overlay" => parse_overlay(working_set, spans),
+        b"hide" => parse_hide(working_set, spans),
+        b"export" => (
+            garbage_statement(spans),
+            Some(ParseError::UnexpectedKeyword("export".into(), spans[0])),
+        ),
         _ => {
             let (expr, err) = parse_expression(working_set, spans);
             (Statement::Pipeline(Pipeline::from_vec(vec![expr])), err)
@@ -2626,21 +3036,58 @@ pub fn parse_block(
                             error = err;
                         }
 
+                        let (expr, err) =
+                            parse_redirections(working_set, expr, &command.redirections);
+
+                        if error.is_none() {
+                            error = err;
+                        }
+
                         expr
                     })
                     .collect::<Vec<Expression>>();
 
+                let (output, err) = parse_complete(working_set, output);
+
+                if error.is_none() {
+                    error = err;
+                }
+
                 Statement::Pipeline(Pipeline {
                     expressions: output,
                 })
             } else {
-                let (stmt, err) = parse_statement(working_set, &pipeline.commands[0].parts);
+                let command = &pipeline.commands[0];
+                let (stmt, err) = parse_statement(working_set, &command.parts);
 
                 if error.is_none() {
                     error = err;
                 }
 
-                stmt
+                match stmt {
+                    Statement::Pipeline(Pipeline { mut expressions }) if expressions.len() == 1 => {
+                        let (expr, err) = parse_redirections(
+                            working_set,
+                            expressions.remove(0),
+                            &command.redirections,
+                        );
+
+                        if error.is_none() {
+                            error = err;
+                        }
+
+                        Statement::Pipeline(Pipeline::from_vec(vec![expr]))
+                    }
+                    stmt => {
+                        if let Some((_, span)) = command.redirections.first() {
+                            if error.is_none() {
+                                error = Some(ParseError::RedirectionNotExternal(*span));
+                            }
+                        }
+
+                        stmt
+                    }
+                }
             }
         })
         .into();
@@ -136,3 +136,27 @@ fn lex_comments() {
         }
     );
 }
+
+#[test]
+fn lex_redirections() {
+    let file = b"cmd out> a err> b out+err> c out>d";
+
+    let output = lex(file, 0, &[], &[]);
+
+    assert!(output.1.is_none());
+
+    let contents: Vec<_> = output.0.iter().map(|token| &token.contents).collect();
+    assert_eq!(
+        contents,
+        vec![
+            &TokenContents::Item,
+            &TokenContents::OutGreaterThan,
+            &TokenContents::Item,
+            &TokenContents::ErrGreaterThan,
+            &TokenContents::Item,
+            &TokenContents::OutErrGreaterThan,
+            &TokenContents::Item,
+            &TokenContents::Item,
+        ]
+    );
+}
@@ -1,5 +1,5 @@
 use nu_parser::{lex, lite_parse, LiteBlock, ParseError};
-use nu_protocol::Span;
+use nu_protocol::{ast::Redirection, Span};
 
 fn lite_parse_helper(input: &[u8]) -> Result<LiteBlock, ParseError> {
     let (output, err) = lex(input, 0, &[], &[]);
@@ -245,3 +245,37 @@ let b = 0
 
     Ok(())
 }
+
+#[test]
+fn redirections() -> Result<(), ParseError> {
+    let input = b"cmd arg out> spam.txt err> eggs.txt | other";
+
+    let lite_block = lite_parse_helper(input)?;
+
+    assert_eq!(lite_block.block.len(), 1);
+    assert_eq!(lite_block.block[0].commands.len(), 2);
+
+    let command = &lite_block.block[0].commands[0];
+    assert_eq!(command.parts.len(), 2);
+    assert_eq!(
+        command.redirections,
+        vec![
+            (Redirection::Stdout, Span { start: 13, end: 21 }),
+            (Redirection::Stderr, Span { start: 27, end: 35 }),
+        ]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn redirection_without_target() {
+    assert!(matches!(
+        lite_parse_helper(b"cmd out> | other"),
+        Err(ParseError::KeywordMissingArgument(name, _)) if name == "out>"
+    ));
+    assert!(matches!(
+        lite_parse_helper(b"cmd err>"),
+        Err(ParseError::KeywordMissingArgument(name, _)) if name == "err>"
+    ));
+}
@@ -1,9 +1,9 @@
 use nu_parser::ParseError;
 use nu_parser::*;
 use nu_protocol::{
-    ast::{Expr, Expression, Pipeline, Statement},
+    ast::{Expr, Expression, ExternalArgument, Pipeline, Statement},
     engine::{Command, EngineState, StateWorkingSet},
-    Signature, SyntaxShape,
+    Signature, Span, SyntaxShape, Type,
 };
 
 #[cfg(test)]
@@ -39,6 +39,67 @@ impl Command for Let {
     }
 }
 
+#[cfg(test)]
+pub struct Group;
+
+#[cfg(test)]
+impl Command for Group {
+    fn name(&self) -> &str {
+        "group"
+    }
+
+    fn usage(&self) -> &str {
+        "Group rows by a column or a block."
+    }
+
+    fn signature(&self) -> nu_protocol::Signature {
+        Signature::build("group").required(
+            "grouper",
+            SyntaxShape::OneOf(vec![
+                SyntaxShape::Block(Some(vec![SyntaxShape::Any])),
+                SyntaxShape::String,
+            ]),
+            "column or block",
+        )
+    }
+
+    fn run(
+        &self,
+        _context: &nu_protocol::engine::EvaluationContext,
+        _call: &nu_protocol::ast::Call,
+        _input: nu_protocol::Value,
+    ) -> Result<nu_protocol::Value, nu_protocol::ShellError> {
+        todo!()
+    }
+}
+
+#[cfg(test)]
+pub struct Use;
+
+#[cfg(test)]
+impl Command for Use {
+    fn name(&self) -> &str {
+        "use"
+    }
+
+    fn usage(&self) -> &str {
+        "Use definitions from a module."
+    }
+
+    fn signature(&self) -> nu_protocol::Signature {
+        Signature::build("use").rest("pattern", SyntaxShape::String, "import pattern parts")
+    }
+
+    fn run(
+        &self,
+        _context: &nu_protocol::engine::EvaluationContext,
+        _call: &nu_protocol::ast::Call,
+        _input: nu_protocol::Value,
+    ) -> Result<nu_protocol::Value, nu_protocol::ShellError> {
+        todo!()
+    }
+}
+
 #[test]
 pub fn parse_int() {
     let engine_state = EngineState::new();
@@ -176,6 +237,295 @@ pub fn parse_call_missing_req_flag() {
     assert!(matches!(err, Some(ParseError::MissingRequiredFlag(..))));
 }
 
+#[test]
+pub fn parse_external_call_args() {
+    let engine_state = EngineState::new();
+    let mut working_set = StateWorkingSet::new(&engine_state);
+
+    let (block, err) = parse(
+        &mut working_set,
+        None,
+        b"^spam 'eggs bacon' *.txt (3) ...[1 2]",
+        true,
+    );
+
+    assert!(err.is_none());
+    assert!(block.len() == 1);
+
+    match &block[0] {
+        Statement::Pipeline(Pipeline { expressions }) => {
+            assert_eq!(expressions.len(), 1);
+
+            if let Expression {
+                expr: Expr::ExternalCall(head, args, ..),
+                ..
+            } = &expressions[0]
+            {
+                assert!(matches!(&head.expr, Expr::String(name) if name == "spam"));
+                assert_eq!(args.len(), 4);
+                assert!(matches!(
+                    &args[0],
+                    ExternalArgument::Regular(Expression {
+                        expr: Expr::String(arg),
+                        ..
+                    }) if arg == "eggs bacon"
+                ));
+                assert!(matches!(
+                    &args[1],
+                    ExternalArgument::Regular(Expression {
+                        expr: Expr::GlobPattern(_),
+                        ..
+                    })
+                ));
+                assert!(matches!(
+                    &args[2],
+                    ExternalArgument::Regular(Expression {
+                        expr: Expr::FullCellPath(_),
+                        ..
+                    })
+                ));
+                assert!(matches!(
+                    &args[3],
+                    ExternalArgument::Spread(
+                        Span { start: 29, end: 32 },
+                        Expression {
+                            expr: Expr::List(_),
+                            ..
+                        }
+                    )
+                ));
+            } else {
+                panic!("not an external call");
+            }
+        }
+        _ => panic!("not an external call"),
+    }
+}
+
+#[test]
+pub fn parse_external_glob_args() {
+    let engine_state = EngineState::new();
+    let mut working_set = StateWorkingSet::new(&engine_state);
+
+    let (block, err) = parse(
+        &mut working_set,
+        None,
+        br#"^spam *.txt file[12].txt spam.txt --name="*.txt" spam?"#,
+        true,
+    );
+
+    assert!(err.is_none());
+
+    match &block[0] {
+        Statement::Pipeline(Pipeline { expressions }) => match &expressions[0].expr {
+            Expr::ExternalCall(_, args, ..) => {
+                let globs: Vec<bool> = args
+                    .iter()
+                    .map(|arg| {
+                        matches!(
+                            arg,
+                            ExternalArgument::Regular(Expression {
+                                expr: Expr::GlobPattern(_),
+                                ..
+                            })
+                        )
+                    })
+                    .collect();
+
+                assert_eq!(globs, vec![true, true, false, false, true]);
+            }
+            _ => panic!("not an external call"),
+        },
+        _ => panic!("not an external call"),
+    }
+}
+
+#[test]
+pub fn parse_string_keeps_backslashes() {
+    let engine_state = EngineState::new();
+    let mut working_set = StateWorkingSet::new(&engine_state);
+
+    let (block, err) = parse(&mut working_set, None, br#""C:\temp \\ \n""#, true);
+
+    assert!(err.is_none());
+    assert!(block.len() == 1);
+    match &block[0] {
+        Statement::Pipeline(Pipeline { expressions }) => {
+            assert!(expressions.len() == 1);
+            assert!(matches!(
+                &expressions[0].expr,
+                Expr::String(val) if val == r"C:\temp \\ \n"
+            ))
+        }
+        _ => panic!("No match"),
+    }
+}
+
+#[test]
+pub fn parse_binary_literal() {
+    let engine_state = EngineState::new();
+    let mut working_set = StateWorkingSet::new(&engine_state);
+
+    let (block, err) = parse(&mut working_set, None, b"0x[1f ff\n 00]", true);
+
+    assert!(err.is_none());
+    assert!(block.len() == 1);
+    match &block[0] {
+        Statement::Pipeline(Pipeline { expressions }) => {
+            assert!(expressions.len() == 1);
+            assert!(matches!(
+                &expressions[0].expr,
+                Expr::Binary(val) if val == &[0x1f, 0xff, 0x00]
+            ))
+        }
+        _ => panic!("No match"),
+    }
+}
+
+#[test]
+pub fn parse_binary_odd_digits() {
+    let engine_state = EngineState::new();
+    let mut working_set = StateWorkingSet::new(&engine_state);
+
+    let (_, err) = parse(&mut working_set, None, b"0x[1f f]", true);
+
+    assert!(matches!(err, Some(ParseError::Expected(..))));
+}
+
+#[test]
+pub fn parse_nothing() {
+    let engine_state = EngineState::new();
+    let mut working_set = StateWorkingSet::new(&engine_state);
+
+    let (block, err) = parse(&mut working_set, None, b"$nothing", true);
+
+    assert!(err.is_none());
+    assert!(block.len() == 1);
+    match &block[0] {
+        Statement::Pipeline(Pipeline { expressions }) => {
+            assert!(expressions.len() == 1);
+            if let Expr::FullCellPath(cell_path) = &expressions[0].expr {
+                assert!(cell_path.tail.is_empty());
+                assert!(matches!(cell_path.head.expr, Expr::Nothing));
+                assert_eq!(cell_path.head.ty, Type::Nothing);
+            } else {
+                panic!("not a cell path");
+            }
+        }
+        _ => panic!("No match"),
+    }
+}
+
+#[test]
+pub fn parse_nothing_is_not_a_variable_prefix() {
+    let engine_state = EngineState::new();
+    let mut working_set = StateWorkingSet::new(&engine_state);
+
+    let (_, err) = parse(&mut working_set, None, b"$nothingness", true);
+
+    assert!(matches!(err, Some(ParseError::VariableNotFound(..))));
+}
+
+#[test]
+pub fn parse_record() {
+    let engine_state = EngineState::new();
+    let mut working_set = StateWorkingSet::new(&engine_state);
+
+    let (block, err) = parse(
+        &mut working_set,
+        None,
+        b"{a: 1, \"b c\": [2 3]\n d:4}",
+        true,
+    );
+
+    assert!(err.is_none());
+    assert!(block.len() == 1);
+    match &block[0] {
+        Statement::Pipeline(Pipeline { expressions }) => {
+            assert!(expressions.len() == 1);
+            if let Expr::Record(fields) = &expressions[0].expr {
+                assert_eq!(fields.len(), 3);
+                assert!(matches!(&fields[0].0.expr, Expr::String(col) if col == "a"));
+                assert!(matches!(&fields[1].0.expr, Expr::String(col) if col == "b c"));
+                assert!(matches!(&fields[1].1.expr, Expr::List(vals) if vals.len() == 2));
+                assert!(matches!(&fields[2].1.expr, Expr::Int(4)));
+            } else {
+                panic!("not a record");
+            }
+        }
+        _ => panic!("No match"),
+    }
+}
+
+#[test]
+pub fn parse_empty_braces_as_record() {
+    let engine_state = EngineState::new();
+    let mut working_set = StateWorkingSet::new(&engine_state);
+
+    let (block, err) = parse(&mut working_set, None, b"{}", true);
+
+    assert!(err.is_none());
+    assert!(block.len() == 1);
+    match &block[0] {
+        Statement::Pipeline(Pipeline { expressions }) => {
+            assert!(expressions.len() == 1);
+            assert!(matches!(&expressions[0].expr, Expr::Record(fields) if fields.is_empty()));
+        }
+        _ => panic!("No match"),
+    }
+}
+
+#[test]
+pub fn parse_block_with_colon_later() {
+    let engine_state = EngineState::new();
+    let mut working_set = StateWorkingSet::new(&engine_state);
+
+    let (block, err) = parse(&mut working_set, None, b"{ 1; 'a:b' }", true);
+
+    assert!(err.is_none());
+    assert!(block.len() == 1);
+    match &block[0] {
+        Statement::Pipeline(Pipeline { expressions }) => {
+            assert!(expressions.len() == 1);
+            assert!(matches!(&expressions[0].expr, Expr::Block(_)));
+        }
+        _ => panic!("No match"),
+    }
+}
+
+#[test]
+pub fn parse_record_missing_value() {
+    let engine_state = EngineState::new();
+    let mut working_set = StateWorkingSet::new(&engine_state);
+
+    let (_, err) = parse(&mut working_set, None, b"{a: 1, b:}", true);
+
+    assert!(matches!(err, Some(ParseError::Expected(..))));
+}
+
+#[test]
+pub fn parse_one_of_shapes() {
+    let engine_state = EngineState::new();
+    let mut working_set = StateWorkingSet::new(&engine_state);
+
+    working_set.add_decl(Box::new(Group));
+
+    for (input, is_block) in [(&b"group name"[..], false), (b"group { $it.name }", true)] {
+        let (block, err) = parse(&mut working_set, None, input, true);
+
+        assert!(err.is_none());
+        match &block[0] {
+            Statement::Pipeline(Pipeline { expressions }) => match &expressions[0].expr {
+                Expr::Call(call) => {
+                    assert_eq!(call.positional[0].as_block().is_some(), is_block);
+                }
+                _ => panic!("not a call"),
+            },
+            _ => panic!("No match"),
+        }
+    }
+}
+
 mod range {
     use super::*;
     use nu_protocol::ast::{RangeInclusion, RangeOperator};
@@ -514,3 +864,23 @@ mod range {
         assert!(err.is_some());
     }
 }
+
+#[test]
+pub fn parse_use_from_lib_dirs() {
+    let lib_dir = std::env::temp_dir().join(format!("nu-parser-lib-dirs-{}", std::process::id()));
+    std::fs::create_dir_all(&lib_dir).expect("can't create the lib dir");
+    std::fs::write(lib_dir.join("spam_lib.nu"), "").expect("can't write the module");
+
+    let engine_state = EngineState::new();
+    let mut working_set = StateWorkingSet::new(&engine_state);
+    working_set.add_decl(Box::new(Use));
+
+    let (_, err) = parse(&mut working_set, None, b"use spam_lib.nu", true);
+    assert!(matches!(err, Some(ParseError::ModuleNotFound(_))));
+
+    working_set.lib_dirs = vec![lib_dir.clone()];
+    let (_, err) = parse(&mut working_set, None, b"use spam_lib.nu", true);
+    assert!(err.is_none());
+
+    let _ = std::fs::remove_dir_all(lib_dir);
+}
@@ -1,6 +1,6 @@
 use std::ops::{Index, IndexMut};
 
-use crate::{DeclId, Signature};
+use crate::{Overlay, Signature};
 
 use super::Statement;
 
@@ -8,7 +8,8 @@ use super::Statement;
 pub struct Block {
     pub signature: Box<Signature>,
     pub stmts: Vec<Statement>,
-    pub exports: Vec<(Vec<u8>, DeclId)>, // Assuming just defs for now
+    pub exports: Overlay,
+    pub redirect_env: bool, // changes to the environment are kept after the block finishes
 }
 
 impl Block {
@@ -46,15 +47,17 @@ impl Block {
         Self {
             signature: Box::new(Signature::new("")),
             stmts: vec![],
-            exports: vec![],
+            exports: Overlay::new(),
+            redirect_env: false,
         }
     }
 
-    pub fn with_exports(self, exports: Vec<(Vec<u8>, DeclId)>) -> Self {
+    pub fn with_exports(self, exports: Overlay) -> Self {
         Self {
             signature: self.signature,
             stmts: self.stmts,
             exports,
+            redirect_env: self.redirect_env,
         }
     }
 }
@@ -67,7 +70,8 @@ where
         Self {
             signature: Box::new(Signature::new("")),
             stmts: stmts.collect(),
-            exports: vec![],
+            exports: Overlay::new(),
+            redirect_env: false,
         }
     }
 }
@@ -35,4 +35,14 @@ impl Call {
 
         false
     }
+
+    pub fn get_flag_expr(&self, flag_name: &str) -> Option<&Expression> {
+        for name in &self.named {
+            if flag_name == name.0 {
+                return name.1.as_ref();
+            }
+        }
+
+        None
+    }
 }
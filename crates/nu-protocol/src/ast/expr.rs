@@ -1,4 +1,4 @@
-use super::{Call, Expression, FullCellPath, Operator, RangeOperator};
+use super::{Call, Expression, FullCellPath, ImportPattern, Operator, RangeOperator};
 use crate::{BlockId, Signature, Span, VarId};
 
 #[derive(Debug, Clone)]
@@ -6,6 +6,8 @@ pub enum Expr {
     Bool(bool),
     Int(i64),
     Float(f64),
+    Binary(Vec<u8>),
+    Nothing,
     Range(
         Option<Box<Expression>>, // from
         Option<Box<Expression>>, // next value after "from"
@@ -14,7 +16,12 @@ pub enum Expr {
     ),
     Var(VarId),
     Call(Box<Call>),
-    ExternalCall(Span, Vec<Span>),
+    ExternalCall(
+        Box<Expression>,
+        Vec<ExternalArgument>,
+        Vec<(Redirection, Expression)>,
+        bool, // piped into `complete`, which needs its stdout, stderr and exit code
+    ),
     Operator(Operator),
     RowCondition(VarId, Box<Expression>),
     BinaryOp(Box<Expression>, Box<Expression>, Box<Expression>), //lhs, op, rhs
@@ -22,9 +29,25 @@ pub enum Expr {
     Block(BlockId),
     List(Vec<Expression>),
     Table(Vec<Expression>, Vec<Vec<Expression>>),
+    Record(Vec<(Expression, Expression)>), // key, value
     Keyword(Vec<u8>, Span, Box<Expression>),
     String(String), // FIXME: improve this in the future?
+    GlobPattern(String),
     FullCellPath(Box<FullCellPath>),
     Signature(Box<Signature>),
+    ImportPattern(ImportPattern),
     Garbage,
 }
+
+#[derive(Debug, Clone)]
+pub enum ExternalArgument {
+    Regular(Expression),
+    Spread(Span, Expression), // `...$list` passes every item of the list as a separate argument
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Redirection {
+    Stdout,          // `out> file`
+    Stderr,          // `err> file`
+    StdoutAndStderr, // `out+err> file`
+}
@@ -1,4 +1,4 @@
-use crate::Span;
+use crate::{BlockId, Span};
 
 #[derive(Debug, Clone)]
 pub enum ImportPatternMember {
@@ -11,4 +11,5 @@ pub enum ImportPatternMember {
 pub struct ImportPattern {
     pub head: Vec<u8>,
     pub members: Vec<ImportPatternMember>,
+    pub module_id: Option<BlockId>, // filled in by the parser once the module is found
 }
@@ -1,7 +1,11 @@
-use super::Command;
-use crate::{ast::Block, BlockId, DeclId, Span, Type, VarId};
+use super::{Command, EnvConversion};
+use crate::{ast::Block, BlockId, DeclId, Overlay, Span, Type, VarId};
 use core::panic;
-use std::{collections::HashMap, slice::Iter};
+use std::{
+    collections::{HashMap, HashSet},
+    path::{Path, PathBuf},
+    slice::Iter,
+};
 
 pub struct EngineState {
     files: Vec<(String, usize, usize)>,
@@ -10,16 +14,82 @@ pub struct EngineState {
     decls: Vec<Box<dyn Command>>,
     blocks: Vec<Block>,
     scope: Vec<ScopeFrame>,
+    module_files: HashMap<PathBuf, BlockId>,
+    env_conversions: HashMap<String, EnvConversion>,
 }
 
+/// The built-in `$env` variable, whose value is built from the environment of the current stack
+pub const ENV_VARIABLE_ID: VarId = 0;
+
 #[derive(Debug)]
 pub struct ScopeFrame {
     vars: HashMap<Vec<u8>, VarId>,
     decls: HashMap<Vec<u8>, DeclId>,
     aliases: HashMap<Vec<u8>, Vec<Span>>,
     modules: HashMap<Vec<u8>, BlockId>,
+    overlays: Vec<OverlayFrame>,   // the last one takes precedence
+    hidden_overlays: Vec<Vec<u8>>, // overlays of the outer frames removed in this one
+    hidden: Visibility,
+    predecls: Vec<(Vec<u8>, DeclId)>, // predeclared definitions whose bodies are not parsed yet
+}
+
+/// Definitions hidden with `hide`. They stay hidden in the frame that hid them and in all the
+/// frames below it, while any definitions they shadowed become visible again.
+#[derive(Debug, Default)]
+struct Visibility {
+    decls: HashSet<DeclId>,
+    aliases: Vec<(Vec<u8>, Vec<Span>)>,
+    vars: HashSet<VarId>,
+}
+
+impl Visibility {
+    fn extend(&mut self, other: &Visibility) {
+        self.decls.extend(&other.decls);
+        self.aliases.extend(other.aliases.iter().cloned());
+        self.vars.extend(&other.vars);
+    }
+
+    fn is_alias_hidden(&self, name: &[u8], replacement: &[Span]) -> bool {
+        self.aliases
+            .iter()
+            .any(|(hidden_name, hidden_replacement)| {
+                hidden_name == name && hidden_replacement == replacement
+            })
+    }
 }
 
+/// A named layer of definitions stacked on top of a scope frame. New definitions go into the
+/// topmost layer, so they can shadow the overlay's own definitions. Removing the overlay restores
+/// whatever its definitions shadowed, while the ones made after it was added are kept.
+#[derive(Debug)]
+pub struct OverlayFrame {
+    pub name: Vec<u8>,
+    decls: HashMap<Vec<u8>, DeclId>,
+    aliases: HashMap<Vec<u8>, Vec<Span>>,
+    pub env_vars: Vec<(Vec<u8>, BlockId)>,
+    // The definitions the overlay was added with
+    module_decls: HashMap<Vec<u8>, DeclId>,
+    module_aliases: HashMap<Vec<u8>, Vec<Span>>,
+}
+
+impl OverlayFrame {
+    pub fn new(name: &[u8]) -> Self {
+        Self {
+            name: name.to_vec(),
+            decls: HashMap::new(),
+            aliases: HashMap::new(),
+            env_vars: vec![],
+            module_decls: HashMap::new(),
+            module_aliases: HashMap::new(),
+        }
+    }
+}
+
+type DefinitionLayer<'a> = (
+    &'a HashMap<Vec<u8>, DeclId>,
+    &'a HashMap<Vec<u8>, Vec<Span>>,
+);
+
 impl ScopeFrame {
     pub fn new() -> Self {
         Self {
@@ -27,12 +97,172 @@ impl ScopeFrame {
             decls: HashMap::new(),
             aliases: HashMap::new(),
             modules: HashMap::new(),
+            overlays: vec![],
+            hidden_overlays: vec![],
+            hidden: Visibility::default(),
+            predecls: vec![],
         }
     }
 
     pub fn get_var(&self, var_name: &[u8]) -> Option<&VarId> {
         self.vars.get(var_name)
     }
+
+    fn insert_decl(&mut self, name: Vec<u8>, decl_id: DeclId) {
+        self.hidden.decls.remove(&decl_id);
+
+        if let Some(overlay) = self.overlays.last_mut() {
+            overlay.decls.insert(name, decl_id);
+        } else {
+            self.decls.insert(name, decl_id);
+        }
+    }
+
+    fn insert_alias(&mut self, name: Vec<u8>, replacement: Vec<Span>) {
+        self.hidden
+            .aliases
+            .retain(|(hidden_name, hidden_replacement)| {
+                hidden_name != &name || hidden_replacement != &replacement
+            });
+
+        if let Some(overlay) = self.overlays.last_mut() {
+            overlay.aliases.insert(name, replacement);
+        } else {
+            self.aliases.insert(name, replacement);
+        }
+    }
+
+    fn add_overlay(&mut self, overlay: OverlayFrame) {
+        self.remove_overlay(&overlay.name);
+        self.overlays.push(overlay);
+    }
+
+    fn remove_overlay(&mut self, name: &[u8]) -> bool {
+        let idx = if let Some(idx) = self.overlays.iter().position(|active| active.name == name) {
+            idx
+        } else {
+            return false;
+        };
+
+        let OverlayFrame {
+            decls,
+            aliases,
+            module_decls,
+            module_aliases,
+            ..
+        } = self.overlays.remove(idx);
+
+        // Definitions made while the overlay was active move to the layer below it
+        let (below_decls, below_aliases) = match idx.checked_sub(1) {
+            Some(below) => {
+                let below = &mut self.overlays[below];
+                (&mut below.decls, &mut below.aliases)
+            }
+            None => (&mut self.decls, &mut self.aliases),
+        };
+
+        for (name, decl_id) in decls {
+            if module_decls.get(&name) != Some(&decl_id) {
+                below_decls.insert(name, decl_id);
+            }
+        }
+
+        for (name, replacement) in aliases {
+            if module_aliases.get(&name) != Some(&replacement) {
+                below_aliases.insert(name, replacement);
+            }
+        }
+
+        true
+    }
+
+    /// Layers of definitions in lookup order, skipping the overlays hidden by inner frames
+    fn layers<'a: 'h, 'h>(
+        &'a self,
+        hidden: &'h [&'h [u8]],
+    ) -> impl Iterator<Item = DefinitionLayer<'a>> + 'h {
+        self.overlays
+            .iter()
+            .rev()
+            .filter(move |overlay| !hidden.contains(&overlay.name.as_slice()))
+            .map(|overlay| (&overlay.decls, &overlay.aliases))
+            .chain(std::iter::once((&self.decls, &self.aliases)))
+    }
+}
+
+/// Go through the layers of definitions visible through the frames (given innermost first) in
+/// lookup order, together with what's hidden at that point, until `f` finds something.
+fn find_in_layers<'a, T>(
+    frames: impl Iterator<Item = &'a ScopeFrame>,
+    mut f: impl FnMut(DefinitionLayer<'a>, &Visibility) -> Option<T>,
+) -> Option<T> {
+    let mut hidden_overlays: Vec<&[u8]> = vec![];
+    let mut hidden = Visibility::default();
+
+    for frame in frames {
+        hidden.extend(&frame.hidden);
+
+        for layer in frame.layers(&hidden_overlays) {
+            if let Some(output) = f(layer, &hidden) {
+                return Some(output);
+            }
+        }
+
+        hidden_overlays.extend(frame.hidden_overlays.iter().map(|name| name.as_slice()));
+    }
+
+    None
+}
+
+fn find_decl_in_layers<'a>(
+    frames: impl Iterator<Item = &'a ScopeFrame>,
+    name: &[u8],
+) -> Option<DeclId> {
+    find_in_layers(frames, |(decls, _), hidden| {
+        decls
+            .get(name)
+            .filter(|decl_id| !hidden.decls.contains(decl_id))
+            .copied()
+    })
+}
+
+fn find_commands_by_prefix_in_layers<'a>(
+    frames: impl Iterator<Item = &'a ScopeFrame>,
+    name: &[u8],
+) -> Vec<Vec<u8>> {
+    let mut output = vec![];
+
+    find_in_layers(frames, |(decls, _), hidden| {
+        for (decl_name, decl_id) in decls {
+            if decl_name.starts_with(name) && !hidden.decls.contains(decl_id) {
+                output.push(decl_name.clone());
+            }
+        }
+
+        None::<()>
+    });
+
+    output
+}
+
+/// Overlays visible through the frames (given innermost first), from the oldest to the newest
+fn visible_overlays<'a>(frames: impl Iterator<Item = &'a ScopeFrame>) -> Vec<&'a OverlayFrame> {
+    let mut hidden: Vec<Vec<u8>> = vec![];
+    let mut output = vec![];
+
+    for frame in frames {
+        output.extend(
+            frame
+                .overlays
+                .iter()
+                .rev()
+                .filter(|overlay| !hidden.contains(&overlay.name)),
+        );
+        hidden.extend(frame.hidden_overlays.iter().cloned());
+    }
+
+    output.reverse();
+    output
 }
 
 impl Default for ScopeFrame {
@@ -52,10 +282,12 @@ impl EngineState {
         Self {
             files: vec![],
             file_contents: vec![],
-            vars: vec![],
+            vars: vec![Type::Unknown],
             decls: vec![],
             blocks: vec![],
             scope: vec![ScopeFrame::new()],
+            module_files: HashMap::new(),
+            env_conversions: HashMap::new(),
         }
     }
 
@@ -66,21 +298,29 @@ impl EngineState {
         this.decls.extend(delta.decls);
         this.vars.extend(delta.vars);
         this.blocks.extend(delta.blocks);
+        this.module_files.extend(delta.module_files);
 
         if let Some(last) = this.scope.last_mut() {
             let first = delta.scope.remove(0);
+            for name in first.hidden_overlays.iter() {
+                last.remove_overlay(name);
+            }
+            last.hidden.extend(&first.hidden);
             for item in first.decls.into_iter() {
-                last.decls.insert(item.0, item.1);
+                last.insert_decl(item.0, item.1);
             }
             for item in first.vars.into_iter() {
                 last.vars.insert(item.0, item.1);
             }
             for item in first.aliases.into_iter() {
-                last.aliases.insert(item.0, item.1);
+                last.insert_alias(item.0, item.1);
             }
             for item in first.modules.into_iter() {
                 last.modules.insert(item.0, item.1);
             }
+            for overlay in first.overlays.into_iter() {
+                last.add_overlay(overlay);
+            }
         }
     }
 
@@ -96,6 +336,14 @@ impl EngineState {
         self.decls.len()
     }
 
+    pub fn add_env_conversion(&mut self, name: &str, conversion: EnvConversion) {
+        self.env_conversions.insert(name.to_string(), conversion);
+    }
+
+    pub fn get_env_conversion(&self, name: &str) -> Option<&EnvConversion> {
+        self.env_conversions.get(name)
+    }
+
     pub fn num_blocks(&self) -> usize {
         self.blocks.len()
     }
@@ -124,27 +372,19 @@ impl EngineState {
     }
 
     pub fn find_decl(&self, name: &[u8]) -> Option<DeclId> {
-        for scope in self.scope.iter().rev() {
-            if let Some(decl_id) = scope.decls.get(name) {
-                return Some(*decl_id);
-            }
-        }
-
-        None
+        find_decl_in_layers(self.scope.iter().rev(), name)
     }
 
     pub fn find_commands_by_prefix(&self, name: &[u8]) -> Vec<Vec<u8>> {
-        let mut output = vec![];
-
-        for scope in self.scope.iter().rev() {
-            for decl in &scope.decls {
-                if decl.0.starts_with(name) {
-                    output.push(decl.0.clone());
-                }
-            }
-        }
+        find_commands_by_prefix_in_layers(self.scope.iter().rev(), name)
+    }
 
-        output
+    /// Names of the active overlays, from the oldest to the newest
+    pub fn active_overlays(&self) -> Vec<Vec<u8>> {
+        visible_overlays(self.scope.iter().rev())
+            .into_iter()
+            .map(|overlay| overlay.name.clone())
+            .collect()
     }
 
     pub fn get_span_contents(&self, span: &Span) -> &[u8] {
@@ -218,6 +458,8 @@ impl EngineState {
 pub struct StateWorkingSet<'a> {
     pub permanent_state: &'a EngineState,
     pub delta: StateDelta,
+    pub parsed_files: Vec<PathBuf>, // files currently being parsed, to detect import cycles
+    pub lib_dirs: Vec<PathBuf>,     // directories searched for module files, from NU_LIB_DIRS
 }
 
 pub struct StateDelta {
@@ -227,6 +469,7 @@ pub struct StateDelta {
     decls: Vec<Box<dyn Command>>, // indexed by DeclId
     blocks: Vec<Block>,           // indexed by BlockId
     pub scope: Vec<ScopeFrame>,
+    module_files: HashMap<PathBuf, BlockId>,
 }
 
 impl StateDelta {
@@ -261,8 +504,11 @@ impl<'a> StateWorkingSet<'a> {
                 decls: vec![],
                 blocks: vec![],
                 scope: vec![ScopeFrame::new()],
+                module_files: HashMap::new(),
             },
             permanent_state,
+            parsed_files: vec![],
+            lib_dirs: vec![],
         }
     }
 
@@ -289,17 +535,71 @@ impl<'a> StateWorkingSet<'a> {
             .scope
             .last_mut()
             .expect("internal error: missing required scope frame");
-        scope_frame.decls.insert(name, decl_id);
+        scope_frame.insert_decl(name, decl_id);
+
+        decl_id
+    }
+
+    /// Make the name refer to an existing definition in the current scope
+    pub fn insert_decl_name(&mut self, name: Vec<u8>, decl_id: DeclId) {
+        let scope_frame = self
+            .delta
+            .scope
+            .last_mut()
+            .expect("internal error: missing required scope frame");
+        scope_frame.insert_decl(name, decl_id);
+    }
+
+    /// Add a definition whose body is parsed later, see `take_predecl`
+    pub fn add_predecl(&mut self, decl: Box<dyn Command>) -> DeclId {
+        let name = decl.name().as_bytes().to_vec();
+        let decl_id = self.add_decl(decl);
+
+        let scope_frame = self
+            .delta
+            .scope
+            .last_mut()
+            .expect("internal error: missing required scope frame");
+        scope_frame.predecls.push((name, decl_id));
 
         decl_id
     }
 
+    /// The oldest predeclaration of the name in the current scope. It's found even if an overlay
+    /// added since then shadows the name.
+    pub fn take_predecl(&mut self, name: &[u8]) -> Option<DeclId> {
+        let scope_frame = self
+            .delta
+            .scope
+            .last_mut()
+            .expect("internal error: missing required scope frame");
+
+        let idx = scope_frame
+            .predecls
+            .iter()
+            .position(|(predecl_name, _)| predecl_name == name)?;
+
+        Some(scope_frame.predecls.remove(idx).1)
+    }
+
     pub fn add_block(&mut self, block: Block) -> BlockId {
         self.delta.blocks.push(block);
 
         self.num_blocks() - 1
     }
 
+    pub fn add_module_file(&mut self, path: PathBuf, block_id: BlockId) {
+        self.delta.module_files.insert(path, block_id);
+    }
+
+    pub fn find_module_file(&self, path: &Path) -> Option<BlockId> {
+        if let Some(block_id) = self.delta.module_files.get(path) {
+            return Some(*block_id);
+        }
+
+        self.permanent_state.module_files.get(path).copied()
+    }
+
     pub fn add_module(&mut self, name: &str, block: Block) -> BlockId {
         let name = name.as_bytes().to_vec();
 
@@ -317,7 +617,7 @@ impl<'a> StateWorkingSet<'a> {
         block_id
     }
 
-    pub fn activate_overlay(&mut self, overlay: Vec<(Vec<u8>, DeclId)>) {
+    pub fn activate_overlay(&mut self, overlay: Overlay) {
         // TODO: This will overwrite all existing definitions in a scope. When we add deactivate,
         // we need to re-think how make it recoverable.
         let scope_frame = self
@@ -326,11 +626,70 @@ impl<'a> StateWorkingSet<'a> {
             .last_mut()
             .expect("internal error: missing required scope frame");
 
-        for (name, decl_id) in overlay {
-            scope_frame.decls.insert(name, decl_id);
+        for (name, decl_id) in overlay.decls {
+            scope_frame.insert_decl(name, decl_id);
+        }
+
+        for (name, replacement) in overlay.aliases {
+            scope_frame.insert_alias(name, replacement);
         }
     }
 
+    /// Stack the definitions on top of the current scope as a named layer
+    pub fn add_overlay(&mut self, name: &[u8], overlay: Overlay) {
+        let mut overlay_frame = OverlayFrame::new(name);
+
+        overlay_frame.decls.extend(overlay.decls.clone());
+        overlay_frame.aliases.extend(overlay.aliases.clone());
+        overlay_frame.env_vars = overlay.env_vars;
+        overlay_frame.module_decls = overlay.decls.into_iter().collect();
+        overlay_frame.module_aliases = overlay.aliases.into_iter().collect();
+
+        self.delta
+            .scope
+            .last_mut()
+            .expect("internal error: missing required scope frame")
+            .add_overlay(overlay_frame);
+    }
+
+    /// Remove a named layer, bringing back the definitions it shadowed. Returns false if there
+    /// is no such active overlay.
+    pub fn remove_overlay(&mut self, name: &[u8]) -> bool {
+        let is_active = self.active_overlays().iter().any(|active| active == name);
+
+        let scope_frame = self
+            .delta
+            .scope
+            .last_mut()
+            .expect("internal error: missing required scope frame");
+
+        if scope_frame.remove_overlay(name) {
+            true
+        } else if is_active {
+            // The overlay belongs to an outer frame, so it can only be hidden from this one
+            scope_frame.hidden_overlays.push(name.to_vec());
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Names of the active overlays, from the oldest to the newest
+    pub fn active_overlays(&self) -> Vec<Vec<u8>> {
+        visible_overlays(self.scope_frames())
+            .into_iter()
+            .map(|overlay| overlay.name.clone())
+            .collect()
+    }
+
+    fn scope_frames(&self) -> impl Iterator<Item = &ScopeFrame> {
+        self.delta
+            .scope
+            .iter()
+            .rev()
+            .chain(self.permanent_state.scope.iter().rev())
+    }
+
     pub fn next_span_start(&self) -> usize {
         self.permanent_state.next_span_start() + self.delta.file_contents.len()
     }
@@ -353,6 +712,16 @@ impl<'a> StateWorkingSet<'a> {
         "<unknown>".into()
     }
 
+    pub fn get_span_filename(&self, span: Span) -> Option<String> {
+        for (filename, start, end) in self.files() {
+            if span.start >= *start && span.start < *end {
+                return Some(filename.clone());
+            }
+        }
+
+        None
+    }
+
     pub fn get_file_source(&self, file_id: usize) -> String {
         for file in self.files().enumerate() {
             if file.0 == file_id {
@@ -401,19 +770,7 @@ impl<'a> StateWorkingSet<'a> {
     }
 
     pub fn find_decl(&self, name: &[u8]) -> Option<DeclId> {
-        for scope in self.delta.scope.iter().rev() {
-            if let Some(decl_id) = scope.decls.get(name) {
-                return Some(*decl_id);
-            }
-        }
-
-        for scope in self.permanent_state.scope.iter().rev() {
-            if let Some(decl_id) = scope.decls.get(name) {
-                return Some(*decl_id);
-            }
-        }
-
-        None
+        find_decl_in_layers(self.scope_frames(), name)
     }
 
     pub fn find_module(&self, name: &[u8]) -> Option<BlockId> {
@@ -438,23 +795,7 @@ impl<'a> StateWorkingSet<'a> {
     // }
 
     pub fn contains_decl_partial_match(&self, name: &[u8]) -> bool {
-        for scope in self.delta.scope.iter().rev() {
-            for decl in &scope.decls {
-                if decl.0.starts_with(name) {
-                    return true;
-                }
-            }
-        }
-
-        for scope in self.permanent_state.scope.iter().rev() {
-            for decl in &scope.decls {
-                if decl.0.starts_with(name) {
-                    return true;
-                }
-            }
-        }
-
-        false
+        !find_commands_by_prefix_in_layers(self.scope_frames(), name).is_empty()
     }
 
     pub fn next_var_id(&self) -> VarId {
@@ -463,15 +804,15 @@ impl<'a> StateWorkingSet<'a> {
     }
 
     pub fn find_variable(&self, name: &[u8]) -> Option<VarId> {
-        for scope in self.delta.scope.iter().rev() {
-            if let Some(var_id) = scope.vars.get(name) {
-                return Some(*var_id);
-            }
-        }
+        let mut hidden: HashSet<VarId> = HashSet::new();
+
+        for scope in self.scope_frames() {
+            hidden.extend(&scope.hidden.vars);
 
-        for scope in self.permanent_state.scope.iter().rev() {
             if let Some(var_id) = scope.vars.get(name) {
-                return Some(*var_id);
+                if !hidden.contains(var_id) {
+                    return Some(*var_id);
+                }
             }
         }
 
@@ -479,19 +820,49 @@ impl<'a> StateWorkingSet<'a> {
     }
 
     pub fn find_alias(&self, name: &[u8]) -> Option<&[Span]> {
-        for scope in self.delta.scope.iter().rev() {
-            if let Some(spans) = scope.aliases.get(name) {
-                return Some(spans);
-            }
-        }
+        find_in_layers(self.scope_frames(), |(_, aliases), hidden| {
+            aliases
+                .get(name)
+                .filter(|replacement| !hidden.is_alias_hidden(name, replacement))
+                .map(|replacement| replacement.as_slice())
+        })
+    }
 
-        for scope in self.permanent_state.scope.iter().rev() {
-            if let Some(spans) = scope.aliases.get(name) {
-                return Some(spans);
-            }
+    /// Hide a command in the current scope, revealing the one it shadowed, if any
+    pub fn hide_decl(&mut self, decl_id: DeclId) {
+        self.delta
+            .scope
+            .last_mut()
+            .expect("internal error: missing required scope frame")
+            .hidden
+            .decls
+            .insert(decl_id);
+    }
+
+    /// Hide an alias in the current scope, revealing the one it shadowed, if any
+    pub fn hide_alias(&mut self, name: &[u8]) {
+        if let Some(replacement) = self.find_alias(name) {
+            let replacement = replacement.to_vec();
+
+            self.delta
+                .scope
+                .last_mut()
+                .expect("internal error: missing required scope frame")
+                .hidden
+                .aliases
+                .push((name.to_vec(), replacement));
         }
+    }
 
-        None
+    /// Hide a variable in the current scope, revealing the one it shadowed, if any
+    pub fn hide_variable(&mut self, var_id: VarId) {
+        self.delta
+            .scope
+            .last_mut()
+            .expect("internal error: missing required scope frame")
+            .hidden
+            .vars
+            .insert(var_id);
     }
 
     pub fn add_variable(&mut self, mut name: Vec<u8>, ty: Type) -> VarId {
@@ -522,7 +893,7 @@ impl<'a> StateWorkingSet<'a> {
             .last_mut()
             .expect("internal error: missing stack frame");
 
-        last.aliases.insert(name, replacement);
+        last.insert_alias(name, replacement);
     }
 
     pub fn set_variable_type(&mut self, var_id: VarId, ty: Type) {
@@ -572,21 +943,7 @@ impl<'a> StateWorkingSet<'a> {
     }
 
     pub fn find_commands_by_prefix(&self, name: &[u8]) -> Vec<Vec<u8>> {
-        let mut output = vec![];
-
-        for scope in self.delta.scope.iter().rev() {
-            for decl in &scope.decls {
-                if decl.0.starts_with(name) {
-                    output.push(decl.0.clone());
-                }
-            }
-        }
-
-        let mut permanent = self.permanent_state.find_commands_by_prefix(name);
-
-        output.append(&mut permanent);
-
-        output
+        find_commands_by_prefix_in_layers(self.scope_frames(), name)
     }
 
     pub fn get_block(&self, block_id: BlockId) -> &Block {
@@ -601,6 +958,18 @@ impl<'a> StateWorkingSet<'a> {
         }
     }
 
+    pub fn get_block_mut(&mut self, block_id: BlockId) -> &mut Block {
+        let num_permanent_blocks = self.permanent_state.num_blocks();
+        if block_id < num_permanent_blocks {
+            panic!("Internal error: attempted to access permanent block from working set")
+        } else {
+            self.delta
+                .blocks
+                .get_mut(block_id - num_permanent_blocks)
+                .expect("internal error: missing block")
+        }
+    }
+
     pub fn render(self) -> StateDelta {
         self.delta
     }
@@ -728,4 +1097,35 @@ mod engine_state_tests {
         assert_eq!(&engine_state.files[0].0, "test.nu");
         assert_eq!(&engine_state.files[1].0, "child.nu");
     }
+
+    #[test]
+    fn merge_overlays() {
+        let mut engine_state = EngineState::new();
+
+        let delta = {
+            let mut working_set = StateWorkingSet::new(&engine_state);
+            working_set.add_overlay(b"spam", Overlay::new());
+            working_set.add_overlay(b"eggs", Overlay::new());
+            working_set.render()
+        };
+
+        EngineState::merge_delta(&mut engine_state, delta);
+
+        assert_eq!(
+            engine_state.active_overlays(),
+            vec![b"spam".to_vec(), b"eggs".to_vec()]
+        );
+
+        let delta = {
+            let mut working_set = StateWorkingSet::new(&engine_state);
+            assert!(working_set.remove_overlay(b"spam"));
+            assert!(!working_set.remove_overlay(b"spam"));
+            assert_eq!(working_set.active_overlays(), vec![b"eggs".to_vec()]);
+            working_set.render()
+        };
+
+        EngineState::merge_delta(&mut engine_state, delta);
+
+        assert_eq!(engine_state.active_overlays(), vec![b"eggs".to_vec()]);
+    }
 }
@@ -0,0 +1,11 @@
+use crate::{ShellError, Span, Value};
+
+/// Converts an environment variable between the string form the operating system uses and the
+/// structured value scripts work with
+#[derive(Debug, Clone, Copy)]
+pub struct EnvConversion {
+    /// Used when the variable is read from the operating system
+    pub from_string: fn(&str, Span) -> Value,
+    /// Used when the variable is passed on to external commands
+    pub to_string: fn(&Value) -> Result<String, ShellError>,
+}
@@ -1,5 +1,9 @@
 use super::EngineState;
-use std::{cell::RefCell, collections::HashMap, rc::Rc};
+use std::{
+    cell::RefCell,
+    collections::{HashMap, HashSet},
+    rc::Rc,
+};
 
 use crate::{ShellError, Value, VarId};
 
@@ -33,25 +37,48 @@ impl EvaluationContext {
                 vals: stream.collect(),
                 span,
             },
+            Value::RawStream { stream, span } => stream.into_value(span),
             x => x,
         };
 
         self.stack.add_var(var_id, value);
     }
 
-    pub fn add_env_var(&self, var: String, value: String) {
+    pub fn add_env_var(&self, var: String, value: Value) {
         self.stack.add_env_var(var, value);
     }
 
+    pub fn hide_env_var(&self, var: String) {
+        self.stack.hide_env_var(var);
+    }
+
+    pub fn add_overlay_env(&self, name: String, env_vars: Vec<(String, Value)>) {
+        self.stack.add_overlay_env(name, env_vars);
+    }
+
+    pub fn remove_overlay_env(&self, name: &str) {
+        self.stack.remove_overlay_env(name);
+    }
+
+    pub fn add_warning(&self, warning: ShellError) {
+        self.stack.add_warning(warning);
+    }
+
     pub fn print_stack(&self) {
         self.stack.print_stack();
     }
 }
 
+/// Name of an overlay and the previous values of the environment variables it set
+type OverlayEnv = (String, Vec<(String, Option<Value>)>);
+
 #[derive(Debug)]
 pub struct StackFrame {
     pub vars: HashMap<VarId, Value>,
-    pub env_vars: HashMap<String, String>,
+    pub env_vars: HashMap<String, Value>,
+    pub hidden_env_vars: HashSet<String>,
+    pub env_overlays: Vec<OverlayEnv>,
+    pub warnings: Vec<ShellError>, // only kept in the root frame, for the CLI to report
     pub parent: Option<Stack>,
 }
 
@@ -69,6 +96,9 @@ impl Stack {
         Stack(Rc::new(RefCell::new(StackFrame {
             vars: HashMap::new(),
             env_vars: HashMap::new(),
+            hidden_env_vars: HashSet::new(),
+            env_overlays: vec![],
+            warnings: vec![],
             parent: None,
         })))
     }
@@ -91,21 +121,128 @@ impl Stack {
         this.vars.insert(var_id, value);
     }
 
-    pub fn add_env_var(&self, var: String, value: String) {
+    pub fn add_env_var(&self, var: String, value: Value) {
         let mut this = self.0.borrow_mut();
+        this.hidden_env_vars.remove(&var);
         this.env_vars.insert(var, value);
     }
 
+    pub fn hide_env_var(&self, var: String) {
+        let mut this = self.0.borrow_mut();
+        this.env_vars.remove(&var);
+        this.hidden_env_vars.insert(var);
+    }
+
+    /// Record a problem that doesn't stop the evaluation
+    pub fn add_warning(&self, warning: ShellError) {
+        let parent = self.0.borrow().parent.clone();
+
+        match parent {
+            Some(parent) => parent.add_warning(warning),
+            None => self.0.borrow_mut().warnings.push(warning),
+        }
+    }
+
+    /// The warnings recorded since the last call
+    pub fn take_warnings(&self) -> Vec<ShellError> {
+        let parent = self.0.borrow().parent.clone();
+
+        match parent {
+            Some(parent) => parent.take_warnings(),
+            None => std::mem::take(&mut self.0.borrow_mut().warnings),
+        }
+    }
+
+    /// Apply the environment changes made in this frame to another frame, usually the caller's
+    pub fn redirect_env(&self, target: &Stack) {
+        let this = self.0.borrow();
+
+        for var in &this.hidden_env_vars {
+            target.hide_env_var(var.clone());
+        }
+
+        for (var, val) in &this.env_vars {
+            target.add_env_var(var.clone(), val.clone());
+        }
+    }
+
+    pub fn add_overlay_env(&self, name: String, env_vars: Vec<(String, Value)>) {
+        // Adding an overlay again must not forget what was there before its first addition
+        self.remove_overlay_env(&name);
+
+        let mut this = self.0.borrow_mut();
+
+        let replaced = env_vars
+            .iter()
+            .map(|(var, _)| (var.clone(), this.env_vars.get(var).cloned()))
+            .collect();
+
+        this.env_vars.extend(env_vars);
+        this.env_overlays.push((name, replaced));
+    }
+
+    pub fn remove_overlay_env(&self, name: &str) {
+        let mut this = self.0.borrow_mut();
+
+        if let Some(idx) = this.env_overlays.iter().rposition(|(n, _)| n == name) {
+            let (_, replaced) = this.env_overlays.remove(idx);
+
+            for (var, value) in replaced {
+                match value {
+                    Some(value) => this.env_vars.insert(var, value),
+                    None => this.env_vars.remove(&var),
+                };
+            }
+        }
+    }
+
     pub fn enter_scope(self) -> Stack {
         Stack(Rc::new(RefCell::new(StackFrame {
             vars: HashMap::new(),
             env_vars: HashMap::new(),
+            hidden_env_vars: HashSet::new(),
+            env_overlays: vec![],
+            warnings: vec![],
             parent: Some(self),
         })))
     }
 
-    pub fn get_env_vars(&self) -> HashMap<String, String> {
-        self.0.borrow().env_vars.clone()
+    /// Look up an environment variable, starting from this frame and moving outwards
+    pub fn get_env_var(&self, name: &str) -> Option<Value> {
+        let this = self.0.borrow();
+
+        if let Some(val) = this.env_vars.get(name) {
+            Some(val.clone())
+        } else if this.hidden_env_vars.contains(name) {
+            None
+        } else if let Some(parent) = &this.parent {
+            parent.get_env_var(name)
+        } else {
+            None
+        }
+    }
+
+    /// Environment variables visible in this frame, where inner frames shadow the outer ones
+    pub fn get_env_vars(&self) -> HashMap<String, Value> {
+        let this = self.0.borrow();
+
+        let mut env_vars = if let Some(parent) = &this.parent {
+            parent.get_env_vars()
+        } else {
+            HashMap::new()
+        };
+
+        for var in &this.hidden_env_vars {
+            env_vars.remove(var);
+        }
+
+        env_vars.extend(
+            this.env_vars
+                .iter()
+                .map(|(var, val)| (var.clone(), val.clone())),
+        );
+
+        env_vars
     }
 
     pub fn print_stack(&self) {
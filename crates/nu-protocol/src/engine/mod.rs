@@ -1,9 +1,11 @@
 mod call_info;
 mod command;
 mod engine_state;
+mod env_conversion;
 mod evaluation_context;
 
 pub use call_info::*;
 pub use command::*;
 pub use engine_state::*;
+pub use env_conversion::*;
 pub use evaluation_context::*;
@@ -0,0 +1,133 @@
+use crate::{
+    ast::{ImportPattern, ImportPatternMember},
+    BlockId, DeclId, Span,
+};
+
+/// Collection of definitions exported from a module, ready to be activated in a scope
+#[derive(Debug, Clone)]
+pub struct Overlay {
+    pub decls: Vec<(Vec<u8>, DeclId)>,
+    pub aliases: Vec<(Vec<u8>, Vec<Span>)>,
+    pub env_vars: Vec<(Vec<u8>, BlockId)>,
+}
+
+impl Default for Overlay {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Overlay {
+    pub fn new() -> Self {
+        Self {
+            decls: vec![],
+            aliases: vec![],
+            env_vars: vec![],
+        }
+    }
+
+    pub fn add_decl(&mut self, name: &[u8], decl_id: DeclId) {
+        self.decls.push((name.to_vec(), decl_id));
+    }
+
+    pub fn add_alias(&mut self, name: &[u8], replacement: Vec<Span>) {
+        self.aliases.push((name.to_vec(), replacement));
+    }
+
+    pub fn add_env_var(&mut self, name: &[u8], block_id: BlockId) {
+        self.env_vars.push((name.to_vec(), block_id));
+    }
+
+    pub fn extend(&mut self, other: Overlay) {
+        self.decls.extend(other.decls);
+        self.aliases.extend(other.aliases);
+        self.env_vars.extend(other.env_vars);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.decls.is_empty() && self.aliases.is_empty() && self.env_vars.is_empty()
+    }
+
+    /// Only keep the definitions called `name`
+    pub fn with_name(&self, name: &[u8]) -> Overlay {
+        Overlay {
+            decls: self
+                .decls
+                .iter()
+                .filter(|(n, _)| n == name)
+                .cloned()
+                .collect(),
+            aliases: self
+                .aliases
+                .iter()
+                .filter(|(n, _)| n == name)
+                .cloned()
+                .collect(),
+            env_vars: self
+                .env_vars
+                .iter()
+                .filter(|(n, _)| n == name)
+                .cloned()
+                .collect(),
+        }
+    }
+
+    /// Put commands and aliases behind the module name (eg. `foo.bar`). Environment variables
+    /// don't have a namespace, so they keep their names.
+    pub fn with_head(&self, head: &[u8]) -> Overlay {
+        let prefixed = |name: &[u8]| {
+            let mut new_name = head.to_vec();
+            new_name.push(b'.');
+            new_name.extend(name);
+            new_name
+        };
+
+        Overlay {
+            decls: self
+                .decls
+                .iter()
+                .map(|(name, id)| (prefixed(name), *id))
+                .collect(),
+            aliases: self
+                .aliases
+                .iter()
+                .map(|(name, replacement)| (prefixed(name), replacement.clone()))
+                .collect(),
+            env_vars: self.env_vars.clone(),
+        }
+    }
+
+    /// Select the definitions matched by an import pattern. If some of the requested members
+    /// were not exported, the span of the first one is returned alongside what could be found.
+    pub fn select(&self, import_pattern: &ImportPattern) -> (Overlay, Option<Span>) {
+        match import_pattern.members.first() {
+            None => (self.with_head(&import_pattern.head), None),
+            Some(ImportPatternMember::Glob { .. }) => (self.clone(), None),
+            Some(ImportPatternMember::Name { name, span }) => {
+                let output = self.with_name(name);
+
+                if output.is_empty() {
+                    (output, Some(*span))
+                } else {
+                    (output, None)
+                }
+            }
+            Some(ImportPatternMember::List { names }) => {
+                let mut output = Overlay::new();
+                let mut missing = None;
+
+                for (name, span) in names {
+                    let found = self.with_name(name);
+
+                    if found.is_empty() {
+                        missing = missing.or(Some(*span));
+                    } else {
+                        output.extend(found);
+                    }
+                }
+
+                (output, missing)
+            }
+        }
+    }
+}
@@ -42,6 +42,22 @@ pub enum ShellError {
     #[diagnostic(code(nu::shell::cant_convert), url(docsrs))]
     CantConvert(String, #[label("can't convert to {0}")] Span),
 
+    #[error("Can't parse as {0}.")]
+    #[diagnostic(code(nu::shell::cant_parse), url(docsrs))]
+    CantParse(String, String, #[label("{1}")] Span),
+
+    #[error("Environment variable {0} can't be converted to a string.")]
+    #[diagnostic(code(nu::shell::env_var_not_a_string), url(docsrs))]
+    EnvVarNotAString(String, #[label("no string conversion for {0}")] Span),
+
+    #[error("Directory not found")]
+    #[diagnostic(code(nu::shell::directory_not_found), url(docsrs))]
+    DirectoryNotFound(#[label("directory not found")] Span),
+
+    #[error("No previous directory")]
+    #[diagnostic(code(nu::shell::no_previous_directory), url(docsrs))]
+    NoPreviousDirectory(#[label("no previous directory to go back to")] Span),
+
     #[error("Division by zero.")]
     #[diagnostic(code(nu::shell::division_by_zero), url(docsrs))]
     DivisionByZero(#[label("division by zero")] Span),
@@ -70,6 +86,10 @@ pub enum ShellError {
     #[diagnostic(code(nu::shell::external_command), url(docsrs))]
     ExternalCommand(String, #[label("{0}")] Span),
 
+    #[error("I/O error")]
+    #[diagnostic(code(nu::shell::io_error), url(docsrs))]
+    IOError(String, #[label("{0}")] Span),
+
     #[error("Unsupported input")]
     #[diagnostic(code(nu::shell::unsupported_input), url(docsrs))]
     UnsupportedInput(String, #[label("{0}")] Span),
@@ -211,7 +211,9 @@ impl Signature {
         });
 
         let name = {
+            // Long flags can be declared with or without their leading dashes
             let name: String = name.into();
+            let name = name.strip_prefix("--").map(String::from).unwrap_or(name);
             debug_assert!(
                 !self.get_names().contains(&name.as_str()),
                 "There may be duplicate name flags, such as --help"
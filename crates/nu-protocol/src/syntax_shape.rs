@@ -75,6 +75,9 @@ pub enum SyntaxShape {
 
     /// A custom shape with custom completion logic
     Custom(Box<SyntaxShape>, String),
+
+    /// Any of the given shapes is allowed, the first one that matches is used
+    OneOf(Vec<SyntaxShape>),
 }
 
 impl SyntaxShape {
@@ -99,6 +102,7 @@ impl SyntaxShape {
             SyntaxShape::Keyword(_, expr) => expr.to_type(),
             SyntaxShape::MathExpression => Type::Unknown,
             SyntaxShape::Number => Type::Number,
+            SyntaxShape::OneOf(_) => Type::Unknown,
             SyntaxShape::Operator => Type::Unknown,
             SyntaxShape::Range => Type::Unknown,
             SyntaxShape::RowCondition => Type::Bool,
@@ -18,6 +18,7 @@ pub enum Type {
     Record(Vec<String>, Vec<Type>),
     Table,
     ValueStream,
+    RawStream,
     Unknown,
     Error,
     Binary,
@@ -42,6 +43,7 @@ impl Display for Type {
             Type::Number => write!(f, "number"),
             Type::String => write!(f, "string"),
             Type::ValueStream => write!(f, "value stream"),
+            Type::RawStream => write!(f, "raw stream"),
             Type::Unknown => write!(f, "unknown"),
             Type::Error => write!(f, "error"),
             Type::Binary => write!(f, "binary"),
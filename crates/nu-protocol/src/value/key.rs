@@ -0,0 +1,72 @@
+use std::cmp::Ordering;
+use std::hash::{Hash, Hasher};
+
+use crate::ast::RangeInclusion;
+use crate::Value;
+
+/// A value used as the key of a map or set, like to find duplicates. Keys are equal when their
+/// values are the same by `Value::compare`, which isn't always what `==` says about them.
+#[derive(Debug, Clone)]
+pub struct ValueKey(pub Value);
+
+impl PartialEq for ValueKey {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for ValueKey {}
+
+impl PartialOrd for ValueKey {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for ValueKey {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.compare(&other.0)
+    }
+}
+
+// Keys that are equal have to hash the same, so this follows `Value::compare`
+impl Hash for ValueKey {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        hash_value(&self.0, state)
+    }
+}
+
+fn hash_value<H: Hasher>(value: &Value, state: &mut H) {
+    std::mem::discriminant(value).hash(state);
+
+    match value {
+        Value::Bool { val, .. } => val.hash(state),
+        Value::Int { val, .. } => val.hash(state),
+        Value::Float { val, .. } => val.to_bits().hash(state),
+        Value::String { val, .. } => val.hash(state),
+        Value::Binary { val, .. } => val.hash(state),
+        Value::Range { val, .. } => {
+            hash_value(&val.from, state);
+            hash_value(&val.incr, state);
+            hash_value(&val.to, state);
+            matches!(val.inclusion, RangeInclusion::Inclusive).hash(state);
+        }
+        Value::List { vals, .. } => hash_values(vals, state),
+        Value::Record { cols, vals, .. } => {
+            cols.hash(state);
+            hash_values(vals, state);
+        }
+        Value::Block { val, .. } => val.hash(state),
+        Value::Stream { .. }
+        | Value::RawStream { .. }
+        | Value::Error { .. }
+        | Value::Nothing { .. } => {}
+    }
+}
+
+fn hash_values<H: Hasher>(vals: &[Value], state: &mut H) {
+    vals.len().hash(state);
+    for val in vals {
+        hash_value(val, state);
+    }
+}
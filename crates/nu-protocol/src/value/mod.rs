@@ -1,14 +1,19 @@
+mod key;
 mod range;
+mod raw_stream;
 mod row;
 mod stream;
 
+pub use key::*;
 pub use range::*;
+pub use raw_stream::*;
 pub use row::*;
 pub use stream::*;
 
+use std::cmp::Ordering;
 use std::fmt::Debug;
 
-use crate::ast::PathMember;
+use crate::ast::{PathMember, RangeInclusion};
 use crate::{span, BlockId, Span, Type};
 
 use crate::ShellError;
@@ -45,6 +50,10 @@ pub enum Value {
         stream: ValueStream,
         span: Span,
     },
+    RawStream {
+        stream: RawStream,
+        span: Span,
+    },
     List {
         vals: Vec<Value>,
         span: Span,
@@ -86,6 +95,7 @@ impl Value {
             Value::List { span, .. } => *span,
             Value::Block { span, .. } => *span,
             Value::Stream { span, .. } => *span,
+            Value::RawStream { span, .. } => *span,
             Value::Nothing { span, .. } => *span,
             Value::Binary { span, .. } => *span,
         }
@@ -101,6 +111,7 @@ impl Value {
             Value::String { span, .. } => *span = new_span,
             Value::Record { span, .. } => *span = new_span,
             Value::Stream { span, .. } => *span = new_span,
+            Value::RawStream { span, .. } => *span = new_span,
             Value::List { span, .. } => *span = new_span,
             Value::Block { span, .. } => *span = new_span,
             Value::Nothing { span, .. } => *span = new_span,
@@ -126,6 +137,7 @@ impl Value {
             Value::Nothing { .. } => Type::Nothing,
             Value::Block { .. } => Type::Block,
             Value::Stream { .. } => Type::ValueStream,
+            Value::RawStream { .. } => Type::RawStream,
             Value::Error { .. } => Type::Error,
             Value::Binary { .. } => Type::Binary,
         }
@@ -148,6 +160,7 @@ impl Value {
             }
             Value::String { val, .. } => val,
             Value::Stream { stream, .. } => stream.into_string(),
+            Value::RawStream { stream, .. } => stream.into_string(),
             Value::List { vals: val, .. } => format!(
                 "[{}]",
                 val.into_iter()
@@ -295,6 +308,26 @@ impl Value {
     }
 }
 
+impl Value {
+    // Values of different types are ordered by their type, with nothing after everything else
+    fn type_order(&self) -> u8 {
+        match self {
+            Value::Bool { .. } => 0,
+            Value::Int { .. } | Value::Float { .. } => 1,
+            Value::String { .. } => 2,
+            Value::Binary { .. } => 3,
+            Value::Range { .. } => 4,
+            Value::List { .. } => 5,
+            Value::Record { .. } => 6,
+            Value::Block { .. } => 7,
+            Value::Stream { .. } => 8,
+            Value::RawStream { .. } => 9,
+            Value::Error { .. } => 10,
+            Value::Nothing { .. } => 11,
+        }
+    }
+}
+
 impl PartialEq for Value {
     fn eq(&self, other: &Self) -> bool {
         match (self, other) {
@@ -308,6 +341,65 @@ impl PartialEq for Value {
     }
 }
 
+impl Value {
+    /// The order used to sort values. Unlike `==`, every value is equal to itself, so it can be
+    /// used to sort and to find duplicates. Ints and floats are compared as numbers, but an int
+    /// is never equal to a float. Streams and errors can't be looked into, so they are all equal
+    /// to each other.
+    pub fn compare(&self, other: &Value) -> Ordering {
+        match (self, other) {
+            (Value::Bool { val: lhs, .. }, Value::Bool { val: rhs, .. }) => lhs.cmp(rhs),
+            (Value::Int { val: lhs, .. }, Value::Int { val: rhs, .. }) => lhs.cmp(rhs),
+            (Value::Float { val: lhs, .. }, Value::Float { val: rhs, .. }) => lhs.total_cmp(rhs),
+            (Value::Int { val: lhs, .. }, Value::Float { val: rhs, .. }) => {
+                (*lhs as f64).total_cmp(rhs).then(Ordering::Less)
+            }
+            (Value::Float { val: lhs, .. }, Value::Int { val: rhs, .. }) => {
+                lhs.total_cmp(&(*rhs as f64)).then(Ordering::Greater)
+            }
+            (Value::String { val: lhs, .. }, Value::String { val: rhs, .. }) => lhs.cmp(rhs),
+            (Value::Binary { val: lhs, .. }, Value::Binary { val: rhs, .. }) => lhs.cmp(rhs),
+            (Value::Range { val: lhs, .. }, Value::Range { val: rhs, .. }) => lhs
+                .from
+                .compare(&rhs.from)
+                .then_with(|| lhs.incr.compare(&rhs.incr))
+                .then_with(|| lhs.to.compare(&rhs.to))
+                .then_with(|| is_inclusive(lhs.inclusion).cmp(&is_inclusive(rhs.inclusion))),
+            (Value::List { vals: lhs, .. }, Value::List { vals: rhs, .. }) => {
+                compare_lists(lhs, rhs)
+            }
+            (
+                Value::Record {
+                    cols: lhs_cols,
+                    vals: lhs_vals,
+                    ..
+                },
+                Value::Record {
+                    cols: rhs_cols,
+                    vals: rhs_vals,
+                    ..
+                },
+            ) => lhs_cols
+                .cmp(rhs_cols)
+                .then_with(|| compare_lists(lhs_vals, rhs_vals)),
+            (Value::Block { val: lhs, .. }, Value::Block { val: rhs, .. }) => lhs.cmp(rhs),
+            _ => self.type_order().cmp(&other.type_order()),
+        }
+    }
+}
+
+fn compare_lists(lhs: &[Value], rhs: &[Value]) -> Ordering {
+    lhs.iter()
+        .zip(rhs)
+        .map(|(lhs, rhs)| lhs.compare(rhs))
+        .find(|ordering| *ordering != Ordering::Equal)
+        .unwrap_or_else(|| lhs.len().cmp(&rhs.len()))
+}
+
+fn is_inclusive(inclusion: RangeInclusion) -> bool {
+    matches!(inclusion, RangeInclusion::Inclusive)
+}
+
 impl Value {
     pub fn add(&self, op: Span, rhs: &Value) -> Result<Value, ShellError> {
         let span = span(&[self.span(), rhs.span()]);
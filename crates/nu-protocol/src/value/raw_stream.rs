@@ -0,0 +1,373 @@
+use crate::*;
+use std::{
+    cell::RefCell,
+    fmt::Debug,
+    io::{ErrorKind, Read, Write},
+    process::{Child, ChildStdin, ChildStdout, ExitStatus},
+    rc::Rc,
+    sync::mpsc,
+    time::Duration,
+};
+
+/// Size of the chunks the bytes are read in
+const CHUNK_SIZE: usize = 8192;
+
+/// Number of chunks that can be read ahead before the reader waits for them to be consumed
+const CHANNEL_CAPACITY: usize = 16;
+
+/// How long to wait for output before handing more input over to the process
+const FEED_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Raw bytes, like the output of an external command or the contents of a file. When the output of
+/// an external is piped into another one the pipe itself is handed over, and the bytes are only
+/// decoded when an internal command needs values.
+#[derive(Clone)]
+pub struct RawStream(pub Rc<RefCell<RawStreamState>>);
+
+pub struct RawStreamState {
+    pub source: RawStreamSource,
+    /// The processes writing the bytes, waited for once they have all been read. The last one
+    /// writes to the pipe, the others are the externals piped into it.
+    processes: Vec<Process>,
+    /// Input of the last process, handed over to it while its output is read
+    feeder: Option<StdinFeeder>,
+}
+
+pub enum RawStreamSource {
+    /// The stdout of a process that hasn't been read from yet
+    Pipe(ChildStdout),
+    /// The stdout of a process being read by a background thread
+    Channel(mpsc::Receiver<Vec<u8>>),
+    Chunks(Box<dyn Iterator<Item = Vec<u8>>>),
+    Done,
+}
+
+/// A running external command. Its exit status is only reported when it's waited for.
+pub struct Process {
+    child: Child,
+    on_exit: Box<dyn FnOnce(ExitStatus)>,
+}
+
+impl Process {
+    pub fn new(child: Child, on_exit: impl FnOnce(ExitStatus) + 'static) -> Process {
+        Process {
+            child,
+            on_exit: Box::new(on_exit),
+        }
+    }
+
+    /// Wait for the process to exit and report its exit status
+    pub fn wait(mut self) {
+        if let Ok(status) = self.child.wait() {
+            (self.on_exit)(status);
+        }
+    }
+}
+
+/// Input written to the stdin of a process by a thread of its own. Values can't leave the engine
+/// thread, so the engine hands the chunks over through a bounded channel, and only gets a few
+/// chunks ahead of the process.
+pub struct StdinFeeder {
+    input: Box<dyn Iterator<Item = Vec<u8>>>,
+    pending: Option<Vec<u8>>,
+    tx: mpsc::SyncSender<Vec<u8>>,
+}
+
+impl StdinFeeder {
+    pub fn new(input: impl Iterator<Item = Vec<u8>> + 'static, mut stdin: ChildStdin) -> Self {
+        let (tx, rx) = mpsc::sync_channel::<Vec<u8>>(CHANNEL_CAPACITY);
+
+        std::thread::spawn(move || {
+            for chunk in rx {
+                if stdin.write_all(&chunk).is_err() {
+                    break;
+                }
+            }
+        });
+
+        StdinFeeder {
+            input: Box::new(input),
+            pending: None,
+            tx,
+        }
+    }
+
+    /// Hand all the input over, waiting for the process whenever the channel is full. Only use
+    /// it when nothing has to read the output of the process in the meantime.
+    pub fn feed_all(self) {
+        for chunk in self.pending.into_iter().chain(self.input) {
+            if self.tx.send(chunk).is_err() {
+                break;
+            }
+        }
+    }
+
+    /// Hand chunks over until the channel is full. Returns false once there is nothing left to
+    /// hand over.
+    fn feed(&mut self) -> bool {
+        while let Some(chunk) = self.pending.take().or_else(|| self.input.next()) {
+            match self.tx.try_send(chunk) {
+                Ok(()) => {}
+                Err(mpsc::TrySendError::Full(chunk)) => {
+                    self.pending = Some(chunk);
+                    return true;
+                }
+                Err(mpsc::TrySendError::Disconnected(_)) => return false,
+            }
+        }
+
+        false
+    }
+}
+
+impl RawStream {
+    fn new(source: RawStreamSource, processes: Vec<Process>) -> RawStream {
+        RawStream(Rc::new(RefCell::new(RawStreamState {
+            source,
+            processes,
+            feeder: None,
+        })))
+    }
+
+    /// The stdout of running processes, the last one writing to the pipe and the others piped
+    /// into it. Once everything was read, the processes are waited for, in order.
+    pub fn from_processes(pipe: ChildStdout, processes: Vec<Process>) -> RawStream {
+        RawStream::new(RawStreamSource::Pipe(pipe), processes)
+    }
+
+    pub fn from_chunks(chunks: impl Iterator<Item = Vec<u8>> + 'static) -> RawStream {
+        RawStream::new(RawStreamSource::Chunks(Box::new(chunks)), vec![])
+    }
+
+    /// Feed the stdin of the last process while the stream is read, so the process never waits
+    /// for input while the engine waits for its output
+    pub fn with_feeder(self, feeder: StdinFeeder) -> RawStream {
+        self.0.borrow_mut().feeder = Some(feeder);
+        self
+    }
+
+    /// Read from the reader in chunks, as the stream gets consumed
+    pub fn from_reader(mut reader: impl Read + 'static) -> RawStream {
+        let chunks = std::iter::from_fn(move || {
+            let mut buf = vec![0; CHUNK_SIZE];
+
+            loop {
+                match reader.read(&mut buf) {
+                    Ok(0) => return None,
+                    Ok(len) => {
+                        buf.truncate(len);
+                        return Some(buf);
+                    }
+                    Err(err) if err.kind() == ErrorKind::Interrupted => continue,
+                    Err(_) => return None,
+                }
+            }
+        });
+
+        RawStream::from_chunks(chunks)
+    }
+
+    /// Take the pipe out of the stream, if nothing has been read from it yet, along with the
+    /// processes to wait for once it's done with. The pipe can then be used as the stdin of another
+    /// process, without the bytes going through the engine. A stream still feeding its process
+    /// has to be read by the engine, so its pipe is kept.
+    pub fn take_pipe(&self) -> Option<(ChildStdout, Vec<Process>)> {
+        let mut state = self.0.borrow_mut();
+
+        if state.feeder.is_some() {
+            return None;
+        }
+
+        match std::mem::replace(&mut state.source, RawStreamSource::Done) {
+            RawStreamSource::Pipe(pipe) => Some((pipe, std::mem::take(&mut state.processes))),
+            other => {
+                state.source = other;
+                None
+            }
+        }
+    }
+
+    /// Read the whole stream, as a string if it is valid UTF-8 and as binary otherwise
+    pub fn into_value(self, span: Span) -> Value {
+        let bytes: Vec<u8> = self.flatten().collect();
+
+        match String::from_utf8(bytes) {
+            Ok(val) => Value::String { val, span },
+            Err(err) => Value::Binary {
+                val: err.into_bytes(),
+                span,
+            },
+        }
+    }
+
+    pub fn into_string(self) -> String {
+        let bytes: Vec<u8> = self.flatten().collect();
+
+        String::from_utf8_lossy(&bytes).into_owned()
+    }
+
+    /// Decode the stream into string values. A character split between two chunks is carried over
+    /// to the next one, and only bytes that aren't UTF-8 at all become binary values.
+    pub fn into_value_stream(self, span: Span) -> ValueStream {
+        ValueStream::from_stream(DecodedStream {
+            raw: self,
+            buffer: vec![],
+            span,
+        })
+    }
+}
+
+impl Debug for RawStream {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RawStream").finish()
+    }
+}
+
+impl Iterator for RawStream {
+    type Item = Vec<u8>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut state = self.0.borrow_mut();
+        let state = &mut *state;
+
+        if let RawStreamSource::Pipe(_) = state.source {
+            if let RawStreamSource::Pipe(pipe) =
+                std::mem::replace(&mut state.source, RawStreamSource::Done)
+            {
+                state.source = RawStreamSource::Channel(read_in_background(pipe));
+            }
+        }
+
+        let chunk = match &mut state.source {
+            RawStreamSource::Channel(rx) => loop {
+                let feeder = match &mut state.feeder {
+                    Some(feeder) => feeder,
+                    None => break rx.recv().ok(),
+                };
+
+                if !feeder.feed() {
+                    // Closes the stdin of the process once it has written everything
+                    state.feeder = None;
+                    continue;
+                }
+
+                match rx.recv_timeout(FEED_INTERVAL) {
+                    Ok(chunk) => break Some(chunk),
+                    Err(mpsc::RecvTimeoutError::Timeout) => {}
+                    Err(mpsc::RecvTimeoutError::Disconnected) => break None,
+                }
+            },
+            RawStreamSource::Chunks(chunks) => chunks.next(),
+            RawStreamSource::Pipe(_) | RawStreamSource::Done => None,
+        };
+
+        if chunk.is_none() {
+            state.source = RawStreamSource::Done;
+            state.feeder = None;
+
+            for process in state.processes.drain(..) {
+                process.wait();
+            }
+        }
+
+        chunk
+    }
+}
+
+impl Drop for RawStreamState {
+    fn drop(&mut self) {
+        // Closing the pipes first lets the processes still using them finish
+        self.source = RawStreamSource::Done;
+        self.feeder = None;
+
+        // The stream wasn't read to the end, so there is no exit status to report, but the
+        // processes still have to be reaped
+        for Process { mut child, .. } in self.processes.drain(..) {
+            std::thread::spawn(move || child.wait());
+        }
+    }
+}
+
+// The channel is bounded, so a process writing faster than its output is consumed gets blocked
+// on its pipe instead of filling up memory
+fn read_in_background(mut pipe: ChildStdout) -> mpsc::Receiver<Vec<u8>> {
+    let (tx, rx) = mpsc::sync_channel(CHANNEL_CAPACITY);
+
+    std::thread::spawn(move || {
+        let mut buf = vec![0; CHUNK_SIZE];
+
+        loop {
+            match pipe.read(&mut buf) {
+                Ok(0) => break,
+                Ok(len) => {
+                    if tx.send(buf[..len].to_vec()).is_err() {
+                        break;
+                    }
+                }
+                Err(err) if err.kind() == ErrorKind::Interrupted => continue,
+                Err(_) => break,
+            }
+        }
+    });
+
+    rx
+}
+
+struct DecodedStream {
+    raw: RawStream,
+    /// Bytes read but not decoded yet, like the start of a character split between two chunks
+    buffer: Vec<u8>,
+    span: Span,
+}
+
+impl Iterator for DecodedStream {
+    type Item = Value;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let (valid_up_to, error_len) = match std::str::from_utf8(&self.buffer) {
+                Ok(_) => (self.buffer.len(), None),
+                Err(err) => (err.valid_up_to(), err.error_len()),
+            };
+
+            if valid_up_to > 0 {
+                let rest = self.buffer.split_off(valid_up_to);
+                let bytes = std::mem::replace(&mut self.buffer, rest);
+
+                return Some(Value::String {
+                    val: String::from_utf8_lossy(&bytes).into_owned(),
+                    span: self.span,
+                });
+            }
+
+            // Bytes that can't be the start of a character are passed on as they are
+            if let Some(mut invalid_len) = error_len {
+                while let Err(err) = std::str::from_utf8(&self.buffer[invalid_len..]) {
+                    match (err.valid_up_to(), err.error_len()) {
+                        (0, Some(error_len)) => invalid_len += error_len,
+                        _ => break,
+                    }
+                }
+
+                let rest = self.buffer.split_off(invalid_len);
+
+                return Some(Value::Binary {
+                    val: std::mem::replace(&mut self.buffer, rest),
+                    span: self.span,
+                });
+            }
+
+            match self.raw.next() {
+                Some(chunk) => self.buffer.extend(chunk),
+                None if self.buffer.is_empty() => return None,
+                // The stream ended in the middle of a character
+                None => {
+                    return Some(Value::Binary {
+                        val: std::mem::take(&mut self.buffer),
+                        span: self.span,
+                    })
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,57 @@
+use nu_protocol::{RawStream, Span, Value};
+
+fn decode(chunks: Vec<Vec<u8>>) -> Vec<Value> {
+    RawStream::from_chunks(chunks.into_iter())
+        .into_value_stream(Span::unknown())
+        .collect()
+}
+
+#[test]
+fn test_character_split_between_chunks() {
+    // "ü" is 0xc3 0xbc
+    let values = decode(vec![b"a\xc3".to_vec(), b"\xbcb".to_vec()]);
+
+    assert_eq!(
+        values,
+        vec![
+            Value::string("a", Span::unknown()),
+            Value::string("üb", Span::unknown())
+        ]
+    );
+}
+
+#[test]
+fn test_invalid_bytes_become_binary() {
+    let values = decode(vec![b"a".to_vec(), b"\xff\xfe".to_vec(), b"b".to_vec()]);
+
+    assert_eq!(values.len(), 3);
+    assert!(matches!(&values[0], Value::String { val, .. } if val == "a"));
+    assert!(matches!(&values[1], Value::Binary { val, .. } if val == b"\xff\xfe"));
+    assert!(matches!(&values[2], Value::String { val, .. } if val == "b"));
+}
+
+#[test]
+fn test_invalid_bytes_inside_a_chunk() {
+    let values = decode(vec![
+        b"sp\xffam".to_vec(),
+        b"\xc3".to_vec(),
+        b"\xbc".to_vec(),
+    ]);
+
+    assert_eq!(values.len(), 4);
+    assert!(matches!(&values[0], Value::String { val, .. } if val == "sp"));
+    assert!(matches!(&values[1], Value::Binary { val, .. } if val == b"\xff"));
+    assert!(matches!(&values[2], Value::String { val, .. } if val == "am"));
+    assert!(matches!(&values[3], Value::String { val, .. } if val == "ü"));
+}
+
+#[test]
+fn test_into_value() {
+    let value = RawStream::from_chunks(vec![b"sp\xc3".to_vec(), b"\xbcam".to_vec()].into_iter())
+        .into_value(Span::unknown());
+    assert_eq!(value, Value::string("spüam", Span::unknown()));
+
+    let value =
+        RawStream::from_chunks(vec![b"\xff".to_vec()].into_iter()).into_value(Span::unknown());
+    assert!(matches!(value, Value::Binary { val, .. } if val == b"\xff"));
+}
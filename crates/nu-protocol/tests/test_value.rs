@@ -0,0 +1,89 @@
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+use nu_protocol::{Span, Value, ValueKey};
+
+fn int(val: i64) -> Value {
+    Value::Int {
+        val,
+        span: Span::unknown(),
+    }
+}
+
+fn float(val: f64) -> Value {
+    Value::Float {
+        val,
+        span: Span::unknown(),
+    }
+}
+
+fn key(value: Value) -> ValueKey {
+    ValueKey(value)
+}
+
+fn hash(value: Value) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    key(value).hash(&mut hasher);
+    hasher.finish()
+}
+
+#[test]
+fn test_sort_mixed_values() {
+    let mut values = vec![
+        Value::nothing(),
+        Value::string("b", Span::unknown()),
+        float(1.5),
+        int(2),
+        Value::string("a", Span::unknown()),
+        int(1),
+    ];
+    values.sort_by(|lhs, rhs| lhs.compare(rhs));
+
+    assert_eq!(
+        values.into_iter().map(key).collect::<Vec<_>>(),
+        vec![
+            int(1),
+            float(1.5),
+            int(2),
+            Value::string("a", Span::unknown()),
+            Value::string("b", Span::unknown()),
+            Value::nothing(),
+        ]
+        .into_iter()
+        .map(key)
+        .collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn test_int_is_not_float() {
+    assert_ne!(key(int(1)), key(float(1.0)));
+    assert!(key(int(1)) < key(float(1.0)));
+    assert!(key(float(1.0)) < key(int(2)));
+}
+
+#[test]
+fn test_equal_values_hash_the_same() {
+    let record = |val| Value::Record {
+        cols: vec!["a".into()],
+        vals: vec![Value::List {
+            vals: vec![int(val), Value::nothing()],
+            span: Span::unknown(),
+        }],
+        span: Span::unknown(),
+    };
+
+    assert_eq!(key(record(1)), key(record(1)));
+    assert_eq!(hash(record(1)), hash(record(1)));
+    assert_ne!(hash(record(1)), hash(record(2)));
+    assert_eq!(key(float(f64::NAN)), key(float(f64::NAN)));
+    assert_eq!(hash(float(f64::NAN)), hash(float(f64::NAN)));
+}
+
+#[test]
+fn test_equality_is_not_the_sort_order() {
+    assert_eq!(float(0.0), float(-0.0));
+    assert_ne!(float(f64::NAN), float(f64::NAN));
+    assert_ne!(Value::nothing(), Value::nothing());
+    assert_ne!(key(float(0.0)), key(float(-0.0)));
+}
@@ -1,14 +1,14 @@
 use std::io::Write;
 
 use miette::{IntoDiagnostic, Result};
-use nu_cli::{report_error, NuCompleter, NuHighlighter, NuValidator};
+use nu_cli::{report_error, report_warning, NuCompleter, NuHighlighter, NuValidator};
 use nu_command::create_default_context;
-use nu_engine::eval_block;
+use nu_engine::{env_from_string, eval_block, lib_dirs, PWD_ENV};
 use nu_parser::parse;
 use nu_protocol::{
     ast::Call,
-    engine::{EngineState, EvaluationContext, StateWorkingSet},
-    ShellError, Value,
+    engine::{EngineState, EvaluationContext, Stack, StateWorkingSet},
+    ShellError, Span, Value,
 };
 use reedline::DefaultCompletionActionHandler;
 
@@ -23,9 +23,14 @@ fn main() -> Result<()> {
     if let Some(path) = std::env::args().nth(1) {
         let file = std::fs::read(&path).into_diagnostic()?;
 
+        let stack = nu_protocol::engine::Stack::new();
+
+        gather_parent_env_vars(&engine_state.borrow(), &stack);
+
         let (block, delta) = {
             let engine_state = engine_state.borrow();
             let mut working_set = StateWorkingSet::new(&*engine_state);
+            working_set.lib_dirs = lib_dirs(&stack);
             let (output, err) = parse(&mut working_set, Some(&path), &file, false);
             if let Some(err) = err {
                 report_error(&working_set, &err);
@@ -39,15 +44,19 @@ fn main() -> Result<()> {
 
         let state = EvaluationContext {
             engine_state: engine_state.clone(),
-            stack: nu_protocol::engine::Stack::new(),
+            stack,
         };
 
         match eval_block(&state, &block, Value::nothing()) {
             Ok(value) => {
                 println!("{}", value.into_string());
+
+                report_warnings(&engine_state.borrow(), &state.stack);
             }
             Err(err) => {
                 let engine_state = engine_state.borrow();
+                report_warnings(&engine_state, &state.stack);
+
                 let working_set = StateWorkingSet::new(&*engine_state);
 
                 report_error(&working_set, &err);
@@ -82,6 +91,8 @@ fn main() -> Result<()> {
         let prompt = DefaultPrompt::new(1);
         let stack = nu_protocol::engine::Stack::new();
 
+        gather_parent_env_vars(&engine_state.borrow(), &stack);
+
         loop {
             entry_num += 1;
 
@@ -110,6 +121,7 @@ fn main() -> Result<()> {
                     let (block, delta) = {
                         let engine_state = engine_state.borrow();
                         let mut working_set = StateWorkingSet::new(&*engine_state);
+                        working_set.lib_dirs = lib_dirs(&stack);
                         let (output, err) = parse(
                             &mut working_set,
                             Some(&format!("entry #{}", entry_num)),
@@ -131,9 +143,15 @@ fn main() -> Result<()> {
                     };
 
                     match eval_block(&state, &block, Value::nothing()) {
-                        Ok(value) => print_value(value, &state)?,
+                        Ok(value) => {
+                            print_value(value, &state)?;
+
+                            report_warnings(&engine_state.borrow(), &stack);
+                        }
                         Err(err) => {
                             let engine_state = engine_state.borrow();
+                            report_warnings(&engine_state, &stack);
+
                             let working_set = StateWorkingSet::new(&*engine_state);
 
                             report_error(&working_set, &err);
@@ -162,6 +180,50 @@ fn main() -> Result<()> {
     }
 }
 
+/// Seed the root stack with the environment the shell was started with. Variables that aren't valid
+/// UTF-8 are kept with the invalid parts replaced, and externals get that lossy version too.
+fn gather_parent_env_vars(engine_state: &EngineState, stack: &Stack) {
+    for (name, val) in std::env::vars_os() {
+        let (name, val) = match (name.into_string(), val.into_string()) {
+            (Ok(name), Ok(val)) => (name, val),
+            (name, val) => {
+                let name = name.unwrap_or_else(|name| name.to_string_lossy().to_string());
+                let val = val.unwrap_or_else(|val| val.to_string_lossy().to_string());
+
+                eprintln!(
+                    "Warning: environment variable {} is not valid UTF-8, invalid characters were replaced",
+                    name
+                );
+
+                (name, val)
+            }
+        };
+
+        let val = env_from_string(engine_state, &name, &val, Span::unknown());
+        stack.add_env_var(name, val);
+    }
+
+    // The engine keeps track of its own working directory, starting from the one of the process
+    if let Ok(cwd) = std::env::current_dir() {
+        stack.add_env_var(
+            PWD_ENV.into(),
+            Value::String {
+                val: cwd.to_string_lossy().to_string(),
+                span: Span::unknown(),
+            },
+        );
+    }
+}
+
+/// Report the problems that didn't stop the evaluation
+fn report_warnings(engine_state: &EngineState, stack: &Stack) {
+    let working_set = StateWorkingSet::new(engine_state);
+
+    for warning in stack.take_warnings() {
+        report_warning(&working_set, &warning);
+    }
+}
+
 fn print_value(value: Value, state: &EvaluationContext) -> Result<(), ShellError> {
     // If the table function is in the declarations, then we can use it
     // to create the table value that will be printed in the terminal
@@ -1,5 +1,6 @@
 use assert_cmd::prelude::*;
 use pretty_assertions::assert_eq;
+use std::ffi::OsStr;
 use std::io::Write;
 use std::process::Command;
 use tempfile::NamedTempFile;
@@ -54,6 +55,71 @@ fn fail_test(input: &str, expected: &str) -> TestResult {
     Ok(())
 }
 
+#[cfg(test)]
+fn run_in_dir(
+    files: &[(&str, &str)],
+    input: &str,
+    lib_dir: Option<&str>,
+) -> Result<(String, String), Box<dyn std::error::Error>> {
+    let dir = tempfile::tempdir()?;
+
+    for (name, contents) in files {
+        let path = dir.path().join(name);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, contents)?;
+    }
+
+    let script = dir.path().join("main.nu");
+    std::fs::write(&script, input)?;
+
+    let mut cmd = Command::cargo_bin("engine-q")?;
+    cmd.arg(&script);
+    cmd.current_dir(dir.path());
+
+    if let Some(lib_dir) = lib_dir {
+        cmd.env("NU_LIB_DIRS", dir.path().join(lib_dir));
+    }
+
+    let output = cmd.output()?;
+
+    let stderr = String::from_utf8_lossy(&output.stderr).to_string();
+    let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+
+    println!("stdout: {}", stdout);
+    println!("stderr: {}", stderr);
+
+    Ok((stdout, stderr))
+}
+
+#[cfg(test)]
+fn run_with_env(
+    input: &str,
+    vars: &[(&str, &OsStr)],
+) -> Result<(String, String), Box<dyn std::error::Error>> {
+    let mut file = NamedTempFile::new()?;
+    let name = file.path();
+
+    let mut cmd = Command::cargo_bin("engine-q")?;
+    cmd.arg(name);
+    cmd.envs(vars.iter().copied());
+
+    writeln!(file, "{}", input)?;
+
+    let output = cmd.output()?;
+
+    let stderr = String::from_utf8_lossy(&output.stderr).to_string();
+    let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+
+    println!("stdout: {}", stdout);
+    println!("stderr: {}", stderr);
+
+    assert!(output.status.success());
+
+    Ok((stdout, stderr))
+}
+
 #[test]
 fn add_simple() -> TestResult {
     run_test("3 + 4", "7")
@@ -171,6 +237,12 @@ fn predecl_check() -> TestResult {
     run_test("def bob [] { sam }; def sam [] { 3 }; bob", "3")
 }
 
+#[test]
+fn def_with_flag() -> TestResult {
+    run_test("def foo [--bar] { 3 }; foo --bar", "3")?;
+    run_test("def foo [--bar(-b)] { 3 }; foo -b", "3")
+}
+
 #[test]
 fn def_with_no_dollar() -> TestResult {
     run_test("def bob [x] { $x + 3 }; bob 4", "7")
@@ -346,7 +418,7 @@ fn better_block_types() -> TestResult {
 #[test]
 fn module_imports_1() -> TestResult {
     run_test(
-        r#"module foo { def a [] { 1 }; def b [] { 2 } }; use foo; foo.a"#,
+        r#"module foo { export def a [] { 1 }; export def b [] { 2 } }; use foo; foo.a"#,
         "1",
     )
 }
@@ -354,7 +426,7 @@ fn module_imports_1() -> TestResult {
 #[test]
 fn module_imports_2() -> TestResult {
     run_test(
-        r#"module foo { def a [] { 1 }; def b [] { 2 } }; use foo.a; a"#,
+        r#"module foo { export def a [] { 1 }; export def b [] { 2 } }; use foo.a; a"#,
         "1",
     )
 }
@@ -362,7 +434,7 @@ fn module_imports_2() -> TestResult {
 #[test]
 fn module_imports_3() -> TestResult {
     run_test(
-        r#"module foo { def a [] { 1 }; def b [] { 2 } }; use foo.*; b"#,
+        r#"module foo { export def a [] { 1 }; export def b [] { 2 } }; use foo.*; b"#,
         "2",
     )
 }
@@ -370,7 +442,7 @@ fn module_imports_3() -> TestResult {
 #[test]
 fn module_imports_4() -> TestResult {
     fail_test(
-        r#"module foo { def a [] { 1 }; def b [] { 2 } }; use foo.c"#,
+        r#"module foo { export def a [] { 1 }; export def b [] { 2 } }; use foo.c"#,
         "not find import",
     )
 }
@@ -378,7 +450,1576 @@ fn module_imports_4() -> TestResult {
 #[test]
 fn module_imports_5() -> TestResult {
     run_test(
-        r#"module foo { def a [] { 1 }; def b [] { 2 }; def c [] { 3 } }; use foo.[a, c]; c"#,
+        r#"module foo { export def a [] { 1 }; def b [] { 2 }; export def c [] { 3 } }; use foo.[a, c]; c"#,
         "3",
     )
 }
+
+#[test]
+fn module_import_uses_internal_command() -> TestResult {
+    run_test(
+        r#"module foo { def b [] { 2 }; export def a [] { b }  }; use foo; foo.a"#,
+        "2",
+    )
+}
+
+#[test]
+fn module_private_def_not_exported() -> TestResult {
+    fail_test(
+        r#"module foo { def a [] { 1 }; export def b [] { 2 } }; use foo.a"#,
+        "not find import",
+    )
+}
+
+#[test]
+fn module_export_alias() -> TestResult {
+    run_test(
+        r#"module foo { export def a [x] { $x + 1 }; export alias b = a 10 }; use foo.*; b"#,
+        "11",
+    )
+}
+
+#[test]
+fn module_export_env() -> TestResult {
+    run_test(
+        r#"module foo { export env FOO { "BAR" } }; use foo.FOO; $env.FOO"#,
+        "BAR",
+    )
+}
+
+#[test]
+fn export_outside_module() -> TestResult {
+    fail_test(r#"export def a [] { 1 }"#, "only in a module")
+}
+
+#[test]
+fn module_file_relative() -> TestResult {
+    let (stdout, _) = run_in_dir(
+        &[("lib/utils.nu", "export def a [] { 1 }; export def b [] { 2 }")],
+        "use lib/utils.nu; utils.b",
+        None,
+    )?;
+
+    assert_eq!(stdout.trim(), "2");
+
+    Ok(())
+}
+
+#[test]
+fn module_file_members() -> TestResult {
+    let (stdout, _) = run_in_dir(
+        &[("utils.nu", "export def a [] { 1 }; export def b [] { 2 }")],
+        "use utils.nu [a, b]; (a) + (b)",
+        None,
+    )?;
+
+    assert_eq!(stdout.trim(), "3");
+
+    Ok(())
+}
+
+#[test]
+fn module_file_imports_relative_to_itself() -> TestResult {
+    let (stdout, _) = run_in_dir(
+        &[
+            ("lib/inner.nu", "export def a [] { 5 }"),
+            ("lib/outer.nu", "use inner.nu a; export def b [] { a }"),
+        ],
+        "use lib/outer.nu b; b",
+        None,
+    )?;
+
+    assert_eq!(stdout.trim(), "5");
+
+    Ok(())
+}
+
+#[test]
+fn module_file_lib_dirs() -> TestResult {
+    let (stdout, _) = run_in_dir(
+        &[("libs/utils.nu", "export def a [] { 1 }")],
+        "use utils.nu *; a",
+        Some("libs"),
+    )?;
+
+    assert_eq!(stdout.trim(), "1");
+
+    Ok(())
+}
+
+#[test]
+fn module_file_not_found() -> TestResult {
+    fail_test("use nonexistent_module_file.nu", "module not found")
+}
+
+#[test]
+fn module_file_cycle() -> TestResult {
+    let (_, stderr) = run_in_dir(
+        &[
+            ("a.nu", "use b.nu; export def a [] { 1 }"),
+            ("b.nu", "use a.nu; export def b [] { 2 }"),
+        ],
+        "use a.nu",
+        None,
+    )?;
+
+    assert!(stderr.contains("detected cycle"));
+
+    Ok(())
+}
+
+#[test]
+fn source_file() -> TestResult {
+    let (stdout, _) = run_in_dir(
+        &[("script.nu", "def foo [] { 10 }; let $x = 5; alias bar = foo")],
+        "source script.nu; (bar) + $x",
+        None,
+    )?;
+
+    assert_eq!(stdout.trim(), "15");
+
+    Ok(())
+}
+
+#[test]
+fn source_file_runs_statements() -> TestResult {
+    let (stdout, _) = run_in_dir(&[("script.nu", "3 + 4")], "source script.nu", None)?;
+
+    assert_eq!(stdout.trim(), "7");
+
+    Ok(())
+}
+
+#[test]
+fn source_file_parse_error() -> TestResult {
+    let (_, stderr) = run_in_dir(&[("script.nu", "def foo")], "source script.nu", None)?;
+
+    assert!(stderr.contains("script.nu"));
+    assert!(stderr.contains("Missing required positional argument"));
+
+    Ok(())
+}
+
+#[test]
+fn source_file_cycle() -> TestResult {
+    let (_, stderr) = run_in_dir(
+        &[("a.nu", "source b.nu"), ("b.nu", "source a.nu")],
+        "source a.nu",
+        None,
+    )?;
+
+    assert!(stderr.contains("detected cycle when sourcing"));
+
+    Ok(())
+}
+
+#[test]
+fn source_non_constant_path() -> TestResult {
+    fail_test(r#"let $x = "script.nu"; source $x"#, "constant file path")
+}
+
+#[test]
+fn overlay_add_and_remove() -> TestResult {
+    fail_test(
+        r#"module spam { export def foo [] { "foo" } }; overlay add spam; overlay remove spam; foo"#,
+        "not found",
+    )?;
+
+    run_test(
+        r#"module spam { export def foo [] { "foo" } }; overlay add spam; foo"#,
+        "foo",
+    )
+}
+
+#[test]
+fn overlay_remove_restores_shadowed() -> TestResult {
+    run_test(
+        r#"def foo [] { "old" }; module spam { export def foo [] { "new" } }; overlay add spam; overlay remove; foo"#,
+        "old",
+    )
+}
+
+#[test]
+fn overlay_shadows_definitions() -> TestResult {
+    run_test(
+        r#"def foo [] { "old" }; module spam { export def foo [] { "new" } }; overlay add spam; foo"#,
+        "new",
+    )
+}
+
+#[test]
+fn overlay_stacking() -> TestResult {
+    run_test(
+        r#"module a { export def foo [] { "a" } }; module b { export def foo [] { "b" } }; overlay add a; overlay add b; overlay remove b; foo"#,
+        "a",
+    )
+}
+
+#[test]
+fn overlay_list() -> TestResult {
+    run_test(
+        r#"module a { export def foo [] { "a" } }; module b { export def foo [] { "b" } }; overlay add a; overlay add b; overlay list | length"#,
+        "2",
+    )
+}
+
+#[test]
+fn overlay_remove_missing() -> TestResult {
+    fail_test(r#"overlay remove spam"#, "not an active overlay")
+}
+
+#[test]
+fn overlay_env() -> TestResult {
+    run_test(
+        r#"module spam { export env FOO { "BAR" } }; overlay add spam; $env.FOO"#,
+        "BAR",
+    )?;
+
+    fail_test(
+        r#"module spam { export env FOO { "BAR" } }; overlay add spam; overlay remove spam; $env.FOO"#,
+        "cannot find column",
+    )
+}
+
+#[test]
+fn overlay_env_restores_shadowed() -> TestResult {
+    run_test(
+        r#"let-env FOO = "old"; module spam { export env FOO { "new" } }; overlay add spam; overlay remove spam; $env.FOO"#,
+        "old",
+    )
+}
+
+#[test]
+fn overlay_remove_keeps_new_definitions() -> TestResult {
+    run_test(
+        r#"module spam { export def foo [] { "foo" } }; overlay add spam; def bar [] { "bar" }; overlay remove spam; bar"#,
+        "bar",
+    )
+}
+
+#[test]
+fn overlay_remove_keeps_redefinitions() -> TestResult {
+    run_test(
+        r#"module spam { export def foo [] { "foo" } }; overlay add spam; def foo [] { "new" }; overlay remove spam; foo"#,
+        "new",
+    )
+}
+
+#[test]
+fn overlay_redefinition_shadows_overlay() -> TestResult {
+    run_test(
+        r#"module spam { export def foo [] { "foo" } }; overlay add spam; def foo [] { "new" }; foo"#,
+        "new",
+    )
+}
+
+#[test]
+fn overlay_keeps_overlay_definition() -> TestResult {
+    run_test(
+        r#"module spam { export def foo [] { "foo" } }; def bar [] { "bar" }; overlay add spam; foo"#,
+        "foo",
+    )
+}
+
+#[test]
+fn hide_def() -> TestResult {
+    fail_test(r#"def foo [] { "foo" }; hide foo; foo"#, "not found")
+}
+
+#[test]
+fn hide_def_restores_shadowed() -> TestResult {
+    run_test(
+        r#"def foo [] { "old" }; do { def foo [] { "new" }; hide foo; foo }"#,
+        "old",
+    )
+}
+
+#[test]
+fn hide_def_in_block() -> TestResult {
+    run_test(r#"def foo [] { "foo" }; do { hide foo }; foo"#, "foo")
+}
+
+#[test]
+fn hide_alias() -> TestResult {
+    fail_test(r#"alias foo = 10; hide foo; foo"#, "not found")
+}
+
+#[test]
+fn hide_variable() -> TestResult {
+    fail_test(r#"let x = 10; hide $x; $x"#, "not found")
+}
+
+#[test]
+fn hide_module_members() -> TestResult {
+    fail_test(
+        r#"module spam { export def foo [] { "foo" }; export def bar [] { "bar" } }; use spam.[foo, bar]; hide spam [foo bar]; bar"#,
+        "not found",
+    )
+}
+
+#[test]
+fn hide_module_member_keeps_others() -> TestResult {
+    fail_test(
+        r#"module spam { export def foo [] { "foo" }; export def bar [] { "bar" } }; use spam.[foo, bar]; hide spam [foo]; foo"#,
+        "not found",
+    )?;
+    run_test(
+        r#"module spam { export def foo [] { "foo" }; export def bar [] { "bar" } }; use spam.[foo, bar]; hide spam [foo]; bar"#,
+        "bar",
+    )
+}
+
+#[test]
+fn hide_module_env() -> TestResult {
+    fail_test(
+        r#"module spam { export env FOO { "foo" }; export env BAR { "bar" } }; use spam.[FOO, BAR]; hide spam.FOO; $env.FOO"#,
+        "cannot find column",
+    )?;
+    run_test(
+        r#"module spam { export env FOO { "foo" }; export env BAR { "bar" } }; use spam.[FOO, BAR]; hide spam.FOO; $env.BAR"#,
+        "bar",
+    )
+}
+
+#[test]
+fn hide_missing() -> TestResult {
+    fail_test(r#"hide foo"#, "not found")
+}
+
+#[test]
+fn env_structured_value() -> TestResult {
+    run_test(r#"let-env FOO = [1 2 3]; $env.FOO | length"#, "3")
+}
+
+#[test]
+fn env_cell_path() -> TestResult {
+    run_test(r#"let-env FOO = "BAR"; $env.FOO"#, "BAR")
+}
+
+#[test]
+fn env_missing_column() -> TestResult {
+    fail_test(r#"$env.FOO"#, "cannot find column")
+}
+
+#[test]
+fn env_list_to_external() -> TestResult {
+    let (stdout, stderr) = run_with_env(r#"let-env FOO = [1 2 3]; ^echo "still runs""#, &[])?;
+
+    assert_eq!(stdout.trim(), "still runs");
+    assert!(stderr.contains("Warning"));
+    assert!(stderr.contains("FOO can't be converted to a string"));
+
+    Ok(())
+}
+
+#[test]
+fn env_from_parent_process() -> TestResult {
+    let (stdout, _) = run_with_env("$env.SPAM", &[("SPAM", OsStr::new("eggs"))])?;
+
+    assert_eq!(stdout.trim(), "eggs");
+
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn env_path_is_list() -> TestResult {
+    let path = std::env::join_paths(["/spam", "/eggs"])?;
+    let (stdout, _) = run_with_env("$env.PATH.1", &[("PATH", &path)])?;
+
+    assert_eq!(stdout.trim(), "/eggs");
+
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn env_invalid_utf8() -> TestResult {
+    use std::os::unix::ffi::OsStrExt;
+
+    let (stdout, stderr) = run_with_env("$env.SPAM", &[("SPAM", OsStr::from_bytes(b"eggs\xff"))])?;
+
+    assert_eq!(stdout.trim(), "eggs\u{fffd}");
+    assert!(stderr.contains("SPAM is not valid UTF-8"));
+
+    Ok(())
+}
+
+#[test]
+fn env_inherited_by_block() -> TestResult {
+    run_test(r#"let-env FOO = "outer"; do { $env.FOO }"#, "outer")
+}
+
+#[test]
+fn env_shadowed_in_block() -> TestResult {
+    run_test(
+        r#"let-env FOO = "outer"; do { let-env FOO = "inner"; $env.FOO }"#,
+        "inner",
+    )
+}
+
+#[test]
+fn env_hidden_in_block() -> TestResult {
+    fail_test(
+        r#"let-env FOO = "outer"; do { hide env FOO; $env.FOO }"#,
+        "cannot find column",
+    )
+}
+
+#[test]
+fn env_change_in_block_is_local() -> TestResult {
+    fail_test(
+        r#"do { let-env FOO = "BAR" }; $env.FOO"#,
+        "cannot find column",
+    )
+}
+
+#[test]
+fn env_change_in_def_is_local() -> TestResult {
+    fail_test(
+        r#"def spam [] { let-env FOO = "BAR" }; spam; $env.FOO"#,
+        "cannot find column",
+    )
+}
+
+#[test]
+fn def_env() -> TestResult {
+    run_test(
+        r#"def-env spam [] { let-env FOO = "BAR" }; spam; $env.FOO"#,
+        "BAR",
+    )
+}
+
+#[test]
+fn def_env_hide() -> TestResult {
+    fail_test(
+        r#"let-env FOO = "BAR"; def-env spam [] { hide env FOO }; spam; $env.FOO"#,
+        "cannot find column",
+    )
+}
+
+#[test]
+fn def_env_nested_block_is_local() -> TestResult {
+    fail_test(
+        r#"def-env spam [] { do { let-env FOO = "BAR" } }; spam; $env.FOO"#,
+        "cannot find column",
+    )
+}
+
+#[test]
+fn export_def_env() -> TestResult {
+    run_test(
+        r#"module spam { export def-env activate [] { let-env FOO = "BAR" } }; use spam.activate; activate; $env.FOO"#,
+        "BAR",
+    )
+}
+
+#[test]
+fn cd_changes_ls() -> TestResult {
+    let (stdout, _) = run_in_dir(
+        &[("spam/eggs.txt", ""), ("spam/bacon.txt", "")],
+        "cd spam; ls | length",
+        None,
+    )?;
+
+    assert_eq!(stdout.trim(), "2");
+
+    Ok(())
+}
+
+#[test]
+fn cd_changes_external() -> TestResult {
+    let (stdout, _) = run_in_dir(&[("spam/eggs.txt", "")], "cd spam; ls; pwd", None)?;
+
+    assert!(stdout.trim().ends_with("spam"));
+
+    Ok(())
+}
+
+#[test]
+fn cd_back() -> TestResult {
+    let (stdout, _) = run_in_dir(
+        &[("spam/eggs.txt", ""), ("bacon.txt", "")],
+        "cd spam; cd -; ls bacon.txt | length",
+        None,
+    )?;
+
+    assert_eq!(stdout.trim(), "1");
+
+    Ok(())
+}
+
+#[test]
+fn cd_in_block_is_local() -> TestResult {
+    let (stdout, _) = run_in_dir(
+        &[("spam/eggs.txt", ""), ("bacon.txt", "")],
+        "do { cd spam }; ls bacon.txt | length",
+        None,
+    )?;
+
+    assert_eq!(stdout.trim(), "1");
+
+    Ok(())
+}
+
+#[test]
+fn cd_missing_dir() -> TestResult {
+    fail_test("cd spam_does_not_exist", "directory not found")
+}
+
+#[test]
+fn external_arg_with_spaces() -> TestResult {
+    run_test(r#"^echo "spam  eggs""#, "spam  eggs")
+}
+
+#[test]
+fn external_arg_not_interpreted() -> TestResult {
+    run_test(r#"^echo "spam; echo $HOME""#, "spam; echo $HOME")
+}
+
+#[test]
+fn external_glob_expansion() -> TestResult {
+    let (stdout, _) = run_in_dir(
+        &[("spam.txt", ""), ("eggs.txt", ""), ("bacon.md", "")],
+        "^echo *.txt",
+        None,
+    )?;
+
+    assert_eq!(stdout.trim(), "eggs.txt spam.txt");
+
+    Ok(())
+}
+
+#[test]
+fn external_quoted_glob_not_expanded() -> TestResult {
+    let (stdout, _) = run_in_dir(&[("spam.txt", "")], "^echo '*.txt'", None)?;
+
+    assert_eq!(stdout.trim(), "*.txt");
+
+    Ok(())
+}
+
+#[test]
+fn external_not_found() -> TestResult {
+    fail_test("^spam_not_a_command", "spam_not_a_command not found")
+}
+
+#[cfg(windows)]
+#[test]
+fn external_cmd_builtin() -> TestResult {
+    run_test("^echo spam", "spam")
+}
+
+#[test]
+fn external_failure() -> TestResult {
+    fail_test(r#"^sh -c "exit 3""#, "exited with code 3")
+}
+
+#[test]
+fn external_failure_in_try() -> TestResult {
+    run_test(r#"try { ^sh -c "exit 3" }; "caught""#, "caught")
+}
+
+#[test]
+fn try_only_catches_external_failures() -> TestResult {
+    fail_test(r#"try { open missing.txt }"#, "missing.txt")
+}
+
+#[test]
+fn try_keeps_errors_outside_the_block() -> TestResult {
+    fail_test(r#"open missing.txt | try { "caught" }"#, "missing.txt")?;
+    fail_test(r#"try { "caught" }; ^sh -c "exit 3""#, "exited with code 3")
+}
+
+#[test]
+fn last_exit_code_in_pipeline() -> TestResult {
+    run_test(
+        r#"^sh -c "echo spam; exit 3" | lines | length; $env.LAST_EXIT_CODE"#,
+        "3",
+    )
+}
+
+#[test]
+fn last_exit_code_success() -> TestResult {
+    run_test(r#"^sh -c "exit 0"; $env.LAST_EXIT_CODE"#, "0")
+}
+
+#[test]
+fn complete_exit_code() -> TestResult {
+    run_test(r#"(^sh -c "exit 3" | complete).exit_code"#, "3")
+}
+
+#[test]
+fn complete_output() -> TestResult {
+    run_test(
+        r#"let out = (^sh -c "echo spam; echo eggs >&2" | complete); $out.stdout + $out.stderr"#,
+        "spam\neggs",
+    )
+}
+
+#[test]
+fn complete_without_external() -> TestResult {
+    fail_test(r#"3 | complete"#, "only works on external commands")
+}
+
+#[test]
+fn complete_record_without_external() -> TestResult {
+    fail_test(
+        r#"let out = (^sh -c "exit 3" | complete); $out | complete"#,
+        "only works on external commands",
+    )
+}
+
+#[test]
+fn external_backtick_args() -> TestResult {
+    run_test(r#"^`echo` `spam` `*.spam`"#, "spam *.spam")
+}
+
+#[test]
+fn external_arg_variable() -> TestResult {
+    run_test(r#"let x = "spam eggs"; ^echo $x"#, "spam eggs")
+}
+
+#[test]
+fn external_arg_cell_path() -> TestResult {
+    run_test(r#"let x = [[name]; [spam]]; ^echo $x.0.name"#, "spam")
+}
+
+#[test]
+fn external_arg_subexpression() -> TestResult {
+    run_test(r#"^echo (3 + 4)"#, "7")
+}
+
+#[test]
+fn external_arg_interpolation() -> TestResult {
+    run_test(r#"let x = "spam"; ^echo $"($x) eggs""#, "spam eggs")
+}
+
+#[test]
+fn external_arg_spread() -> TestResult {
+    run_test(
+        r#"let x = ["spam eggs" bacon]; ^sh -c 'echo $#' sh ...$x"#,
+        "2",
+    )
+}
+
+#[test]
+fn external_arg_list_needs_spread() -> TestResult {
+    fail_test(
+        r#"let x = [spam eggs]; ^echo $x"#,
+        "can't convert to string",
+    )
+}
+
+#[test]
+fn external_pipe_to_external() -> TestResult {
+    run_test(r#"^head -c 100000 /dev/zero | ^wc -c"#, "100000")
+}
+
+#[test]
+fn external_input_larger_than_pipes() -> TestResult {
+    // cat can't take more input until its output is read, which has to happen while the input is
+    // still being handed over
+    run_test(
+        r#"^head -c 1000000 /dev/zero | each { |it| $it } | ^cat | ^wc -c"#,
+        "1000000",
+    )
+}
+
+#[test]
+fn external_output_decoded_across_chunks() -> TestResult {
+    // 3 byte pieces don't line up with the chunks the output is read in, a chunk decoded as
+    // binary would fail the addition and go missing
+    run_test(
+        r#"^sh -c "for i in $(seq 4000); do printf 'aü'; done" | each { |it| $it + "" } | ^wc -c"#,
+        "12000",
+    )
+}
+
+#[test]
+fn lines_skip_empty() -> TestResult {
+    run_test(
+        r#"(^sh -c "printf 'spam\neggs\n\nbacon\n'" | lines).2"#,
+        "bacon",
+    )
+}
+
+#[test]
+fn lines_across_chunks() -> TestResult {
+    // The lines are longer than the chunks the output is read in
+    run_test(
+        r#"^sh -c "for i in 1 2 3; do head -c 10000 /dev/zero | tr '\0' a; echo; done" | lines | length"#,
+        "3",
+    )
+}
+
+#[test]
+fn lines_crlf() -> TestResult {
+    run_test(
+        r#"^sh -c "printf 'spam\r\neggs\r\n'" | lines | each { |it| $it + "!" } | ^sh -c "tr -d '\n'""#,
+        "spam!eggs!",
+    )
+}
+
+#[test]
+fn save_string() -> TestResult {
+    let (stdout, _) = run_in_dir(&[], r#""spam" | save out.txt; ^cat out.txt"#, None)?;
+    assert_eq!(stdout.trim(), "spam");
+    Ok(())
+}
+
+#[test]
+fn save_append() -> TestResult {
+    let (stdout, _) = run_in_dir(
+        &[("out.txt", "spam")],
+        r#""eggs" | save --append out.txt; ^cat out.txt"#,
+        None,
+    )?;
+    assert_eq!(stdout.trim(), "spameggs");
+    Ok(())
+}
+
+#[test]
+fn save_external_output() -> TestResult {
+    let (stdout, _) = run_in_dir(
+        &[],
+        r#"^sh -c "printf 'a\351b'" | save out.bin; ^wc -c out.bin"#,
+        None,
+    )?;
+    assert_eq!(stdout.trim(), "3 out.bin");
+    Ok(())
+}
+
+#[test]
+fn save_custom_converter() -> TestResult {
+    let (stdout, _) = run_in_dir(
+        &[],
+        r#"def "to spam" [] { each { $it + "!" } }; ["a" "b"] | save out.spam; ^cat out.spam"#,
+        None,
+    )?;
+    assert_eq!(stdout.trim(), "a!b!");
+    Ok(())
+}
+
+#[test]
+fn save_structured_without_converter() -> TestResult {
+    fail_test(
+        r#"[1 2 3] | save out.txt"#,
+        "can't save list<unknown> without a converter",
+    )
+}
+
+#[test]
+fn redirect_stdout() -> TestResult {
+    let (stdout, _) = run_in_dir(&[], r#"^echo spam out> out.txt; ^cat out.txt"#, None)?;
+    assert_eq!(stdout.trim(), "spam");
+    Ok(())
+}
+
+#[test]
+fn redirect_stderr() -> TestResult {
+    let (stdout, stderr) = run_in_dir(
+        &[],
+        r#"^sh -c "echo spam; echo eggs >&2" err> err.txt; ^cat err.txt"#,
+        None,
+    )?;
+    assert_eq!(stdout.trim(), "spam\neggs");
+    assert!(!stderr.contains("eggs"));
+    Ok(())
+}
+
+#[test]
+fn redirect_stdout_and_stderr() -> TestResult {
+    let (stdout, _) = run_in_dir(
+        &[],
+        r#"^sh -c "echo spam; echo eggs >&2" out+err> out.txt; ^cat out.txt"#,
+        None,
+    )?;
+    assert_eq!(stdout.trim(), "spam\neggs");
+    Ok(())
+}
+
+#[test]
+fn redirect_internal_command() -> TestResult {
+    fail_test(r#"ls out> out.txt"#, "only external commands")
+}
+
+#[test]
+fn open_text() -> TestResult {
+    let (stdout, _) = run_in_dir(&[("spam.txt", "eggs")], "open spam.txt", None)?;
+    assert_eq!(stdout.trim(), "eggs");
+    Ok(())
+}
+
+#[test]
+fn open_custom_converter() -> TestResult {
+    let (stdout, _) = run_in_dir(
+        &[("eggs.spam", "a\nb\nc\n")],
+        r#"def "from spam" [] { lines | length }; open eggs.spam"#,
+        None,
+    )?;
+    assert_eq!(stdout.trim(), "3");
+    Ok(())
+}
+
+#[test]
+fn open_large_file_streams() -> TestResult {
+    let contents = "spam\n".repeat(300_000);
+    let (stdout, _) = run_in_dir(
+        &[("spam.txt", &contents)],
+        "open spam.txt | lines | length",
+        None,
+    )?;
+    assert_eq!(stdout.trim(), "300000");
+    Ok(())
+}
+
+#[test]
+fn open_binary_to_external() -> TestResult {
+    let (stdout, _) = run_in_dir(
+        &[],
+        r#"^sh -c "printf 'a\351b' > spam.bin"; open spam.bin | ^wc -c"#,
+        None,
+    )?;
+    assert_eq!(stdout.trim(), "3");
+    Ok(())
+}
+
+#[test]
+fn open_missing_file() -> TestResult {
+    let (_, stderr) = run_in_dir(&[], "open spam.txt", None)?;
+    assert!(stderr.contains("nu::shell::io_error"));
+    Ok(())
+}
+
+#[test]
+fn from_json() -> TestResult {
+    run_test(
+        r#"('{"spam": [1, 2.5, true, null], "eggs": "bacon"}' | from json).spam.1"#,
+        "2.5",
+    )
+}
+
+#[test]
+fn from_json_objects() -> TestResult {
+    run_test(
+        r#"('{"spam": 1}
+{"spam": 2}' | from json --objects).1.spam"#,
+        "2",
+    )
+}
+
+#[test]
+fn from_json_objects_error() -> TestResult {
+    fail_test(
+        r#"'{"spam": 1}
+{"spam": }' | from json --objects | to nuon"#,
+        "expected value at line 1 column 10",
+    )
+}
+
+#[cfg(unix)]
+#[test]
+fn from_json_objects_endless() -> TestResult {
+    run_test(r#"(^yes '{"spam": 3}' | from json --objects).2.spam"#, "3")
+}
+
+#[test]
+fn from_json_error_location() -> TestResult {
+    let (_, stderr) = run_in_dir(&[], r#"'{"spam": }' | from json"#, None)?;
+
+    // The label points at the `}` where a value was expected
+    let source = stderr
+        .lines()
+        .find(|line| line.contains(r#"'{"spam": }'"#))
+        .ok_or("missing source line")?;
+    let label = stderr
+        .lines()
+        .find(|line| line.contains('┬'))
+        .ok_or("missing label")?;
+
+    assert!(stderr.contains("expected value"));
+    assert_eq!(
+        label.chars().position(|c| c == '┬'),
+        source.chars().position(|c| c == '}')
+    );
+
+    Ok(())
+}
+
+#[test]
+fn to_json() -> TestResult {
+    run_test(
+        r#"[[spam eggs]; [1 "bacon"]] | to json --raw"#,
+        r#"[{"spam":1,"eggs":"bacon"}]"#,
+    )
+}
+
+#[test]
+fn to_json_roundtrip() -> TestResult {
+    run_test(
+        r#"([[spam eggs]; [1 [2.5 true]]] | to json --indent 4 | from json).0.eggs.0"#,
+        "2.5",
+    )
+}
+
+#[test]
+fn open_and_save_json() -> TestResult {
+    let (stdout, _) = run_in_dir(
+        &[("spam.json", r#"{"eggs": [1, 2, 3]}"#)],
+        "open spam.json | save bacon.json; (open bacon.json).eggs.2",
+        None,
+    )?;
+    assert_eq!(stdout.trim(), "3");
+    Ok(())
+}
+
+#[test]
+fn from_csv() -> TestResult {
+    run_test(
+        r#"("name,size
+spam,10
+eggs,2.5" | from csv | where size < 5).0.name"#,
+        "eggs",
+    )
+}
+
+#[test]
+fn from_csv_column_types() -> TestResult {
+    run_test(
+        r#""n,zip,code
+1,007,a
+2.5,12,1" | from csv | to json --raw"#,
+        r#"[{"n":1.0,"zip":"007","code":"a"},{"n":2.5,"zip":"12","code":"1"}]"#,
+    )
+}
+
+#[test]
+fn from_csv_empty_fields() -> TestResult {
+    run_test(
+        r#""n,name
+1,
+,spam
+3,eggs" | from csv | to json --raw"#,
+        r#"[{"n":1,"name":null},{"n":null,"name":"spam"},{"n":3,"name":"eggs"}]"#,
+    )
+}
+
+#[test]
+fn from_csv_noheaders_separator() -> TestResult {
+    run_test(
+        r#"("spam;10" | from csv --noheaders --separator ';').0.column2"#,
+        "10",
+    )
+}
+
+#[test]
+fn from_tsv() -> TestResult {
+    run_test(
+        r#"(^sh -c "printf 'name\tsize\nspam\t10\n'" | from tsv).0.size"#,
+        "10",
+    )
+}
+
+#[test]
+fn to_csv_union_of_columns() -> TestResult {
+    run_test(
+        r#"('[{"spam": 1}, {"eggs": 2, "spam": 3}]' | from json | to csv | lines).0"#,
+        "spam,eggs",
+    )
+}
+
+#[test]
+fn to_csv_quotes() -> TestResult {
+    run_test(
+        r#"([[spam eggs]; ["a,b" 1]] | to csv | lines).1"#,
+        r#""a,b",1"#,
+    )
+}
+
+#[test]
+fn csv_roundtrip() -> TestResult {
+    run_test(
+        r#"([[spam eggs]; [1 "bacon"]] | to tsv | from tsv).0.eggs"#,
+        "bacon",
+    )
+}
+
+#[test]
+fn open_large_csv() -> TestResult {
+    let contents = format!("spam,eggs\n{}", "1,2\n".repeat(300_000));
+    let (stdout, _) = run_in_dir(
+        &[("spam.csv", &contents)],
+        "open spam.csv | where eggs == 2 | length",
+        None,
+    )?;
+    assert_eq!(stdout.trim(), "300000");
+    Ok(())
+}
+
+#[test]
+fn from_toml() -> TestResult {
+    run_test(
+        r#"("[package]
+name = 'spam'
+authors = ['eggs', 'bacon']
+released = 1979-05-27T07:32:00Z" | from toml).package.authors.1"#,
+        "bacon",
+    )
+}
+
+#[test]
+fn from_toml_datetime() -> TestResult {
+    run_test(
+        r#"("released = 1979-05-27T07:32:00Z" | from toml).released"#,
+        "1979-05-27T07:32:00Z",
+    )
+}
+
+#[test]
+fn toml_roundtrip() -> TestResult {
+    run_test(
+        r#"('{"spam": {"eggs": [1, 2]}, "bacon": 3.5}' | from json | to toml | from toml).spam.eggs.1"#,
+        "2",
+    )
+}
+
+#[test]
+fn to_toml_needs_record() -> TestResult {
+    fail_test(r#"[1 2] | to toml"#, "expected a record")
+}
+
+#[test]
+fn from_yaml() -> TestResult {
+    run_test(
+        r#"("spam:
+  - eggs: 1
+  - eggs: 2" | from yaml).spam.1.eggs"#,
+        "2",
+    )
+}
+
+#[test]
+fn from_yaml_documents() -> TestResult {
+    run_test(
+        r#"("spam: 1
+---
+spam: 2" | from yaml).1.spam"#,
+        "2",
+    )
+}
+
+#[test]
+fn from_yaml_error_location() -> TestResult {
+    let (_, stderr) = run_in_dir(&[], "'spam: 1\n---\neggs: [2' | from yaml", None)?;
+
+    // The label points at the end of the second document, where the sequence isn't closed
+    let label = stderr
+        .lines()
+        .find(|line| line.contains('┬'))
+        .ok_or("missing label")?;
+    let source = stderr
+        .lines()
+        .find(|line| line.contains("eggs: [2"))
+        .ok_or("missing source line")?;
+
+    assert!(stderr.contains("Can't parse as yaml"));
+    assert_eq!(
+        label.chars().position(|c| c == '┬'),
+        source.chars().position(|c| c == '[').map(|idx| idx + 1)
+    );
+
+    Ok(())
+}
+
+#[test]
+fn to_yml() -> TestResult {
+    run_test(r#"([[spam]; [1]] | to yml | from yml).0.spam"#, "1")
+}
+
+#[test]
+fn yaml_roundtrip() -> TestResult {
+    run_test(
+        r#"([[spam eggs]; [1 "bacon"]] | to yaml | from yaml).0.eggs"#,
+        "bacon",
+    )
+}
+
+#[test]
+fn to_yaml_block() -> TestResult {
+    fail_test(r#"[{ 3 }] | to yaml"#, "block can't be converted to yaml")
+}
+
+#[test]
+fn record_literal() -> TestResult {
+    run_test(r#"({spam: 1, "eggs bacon": [2 3]})."eggs bacon".1"#, "3")
+}
+
+#[test]
+fn double_quotes_keep_backslashes() -> TestResult {
+    run_test(r#""C:\temp\new" | to json"#, r#""C:\\temp\\new""#)
+}
+
+#[test]
+fn single_quotes_inside_double_quotes() -> TestResult {
+    run_test(r#""say 'spam'" | to json"#, r#""say 'spam'""#)
+}
+
+#[test]
+fn nothing_literal() -> TestResult {
+    run_test(r#"[1 $nothing] | to nuon"#, "[1, $nothing]")
+}
+
+#[test]
+fn to_nuon() -> TestResult {
+    run_test(
+        r#"[[spam eggs]; [1 'a"b'] [2.0 $nothing]] | to nuon"#,
+        r#"[[spam, eggs]; [1, 'a"b'], [2.0, $nothing]]"#,
+    )
+}
+
+#[test]
+fn to_nuon_both_quotes() -> TestResult {
+    run_test(
+        r#"build-string 'say "hi" ' "'there'" | to nuon"#,
+        r#"('say "hi" ' + "'there'")"#,
+    )
+}
+
+#[test]
+fn nuon_both_quotes_roundtrip() -> TestResult {
+    run_test(
+        r#"let s = (build-string "'" '"' "'"); ($s | to nuon | from nuon) == $s"#,
+        "true",
+    )?;
+    run_test(
+        r#"[[("a'" + '"b')]; [1]] | to nuon | from nuon | to nuon"#,
+        r#"[[("a'" + '"b')]; [1]]"#,
+    )
+}
+
+#[test]
+fn nuon_roundtrip() -> TestResult {
+    run_test(
+        r#"{spam: [1 2.5 $true], eggs: {bacon: 1..<5, "x y": 'C:\a"b'}} | to nuon | from nuon | to nuon"#,
+        r#"{spam: [1, 2.5, $true], eggs: {bacon: 1..<5, "x y": 'C:\a"b'}}"#,
+    )
+}
+
+#[test]
+fn from_nuon_empty_record() -> TestResult {
+    run_test(r#""{ham: {}}" | from nuon | to nuon"#, "{ham: {}}")
+}
+
+#[test]
+fn empty_braces_are_a_record() -> TestResult {
+    run_test(r#"{} | to nuon"#, "{}")
+}
+
+#[test]
+fn empty_braces_block_argument() -> TestResult {
+    run_test(r#"if $false { 3 } else {}"#, "")
+}
+
+#[test]
+fn block_to_nuon() -> TestResult {
+    fail_test(r#"{ 3 } | to nuon"#, "block can't be converted to nuon")
+}
+
+#[test]
+fn nuon_binary_roundtrip() -> TestResult {
+    run_test(
+        r#"({spam: 0x[00 1f ff]} | to nuon | from nuon).spam | to nuon"#,
+        "0x[00 1f ff]",
+    )
+}
+
+#[test]
+fn from_nuon_no_commands() -> TestResult {
+    fail_test(
+        r#""[1 (ls)]" | from nuon"#,
+        "only literal values are allowed",
+    )
+}
+
+#[test]
+fn to_nuon_block() -> TestResult {
+    fail_test(r#"[{ 3 }] | to nuon"#, "block can't be converted to nuon")
+}
+
+#[test]
+fn from_xml() -> TestResult {
+    run_test(
+        r#"(("<spam><eggs id='1'>a &amp; b</eggs><eggs id='2'/></spam>" | from xml).content | where tag == eggs).0.content.0.content"#,
+        "a & b",
+    )
+}
+
+#[test]
+fn from_xml_attributes() -> TestResult {
+    run_test(
+        r#"("<spam><eggs id='1'/><eggs id='2'/></spam>" | from xml).content.1.attributes.id"#,
+        "2",
+    )
+}
+
+#[test]
+fn from_xml_error_location() -> TestResult {
+    fail_test(r#""<spam><eggs></spam>" | from xml"#, "expected 'eggs' tag")
+}
+
+#[test]
+fn to_xml() -> TestResult {
+    run_test(
+        r#"{tag: spam, attributes: {id: '<"1">'}, content: ["a & b", {tag: eggs, content: []}]} | to xml"#,
+        r#"<spam id="&lt;&quot;1&quot;&gt;">a &amp; b<eggs/></spam>"#,
+    )
+}
+
+#[test]
+fn to_xml_pretty() -> TestResult {
+    run_test(
+        r#"build-string "[" ("<spam><eggs>1</eggs><bacon/></spam>" | from xml | to xml --pretty 4 | lines).1 "]""#,
+        "[    <eggs>1</eggs>]",
+    )
+}
+
+#[test]
+fn xml_roundtrip() -> TestResult {
+    run_test(
+        r#""<spam xmlns:x='urn:x' x:id='1'>a <b>&lt;b&gt;</b> c</spam>" | from xml | to xml"#,
+        r#"<spam xmlns:x="urn:x" x:id="1">a <b>&lt;b&gt;</b> c</spam>"#,
+    )
+}
+
+#[test]
+fn xml_roundtrip_whitespace_text() -> TestResult {
+    run_test(
+        r#""<p>spam <b>eggs</b> <i>bacon</i></p>" | from xml | to xml"#,
+        r#"<p>spam <b>eggs</b> <i>bacon</i></p>"#,
+    )
+}
+
+#[test]
+fn from_xml_drops_indentation() -> TestResult {
+    let xml = "\"<spam>\n  <eggs>1</eggs>\n  <bacon/>\n</spam>\"";
+
+    run_test(&format!("({} | from xml).content | length", xml), "2")?;
+    run_test(
+        &format!(
+            r#"build-string "[" ({} | from xml | to xml --pretty 4 | lines).1 "]""#,
+            xml
+        ),
+        "[    <eggs>1</eggs>]",
+    )
+}
+
+#[test]
+fn to_xml_invalid_tag() -> TestResult {
+    fail_test(
+        r#"{tag: "spam eggs", content: []} | to xml"#,
+        "'spam eggs' is not a valid xml name",
+    )
+}
+
+#[test]
+fn to_xml_invalid_attribute() -> TestResult {
+    fail_test(
+        r#"{tag: spam, attributes: {"1id": x}} | to xml"#,
+        "'1id' is not a valid xml name",
+    )
+}
+
+#[test]
+fn to_md() -> TestResult {
+    run_test(
+        r#"([{name: "a|b", size: 10} {name: c, extra: [1 2]}] | to md | lines).3"#,
+        "| c |  | [1, 2] |",
+    )
+}
+
+#[test]
+fn table_headers_from_first_row() -> TestResult {
+    let (stdout, _) = run_in_dir(&[], "[{spam: 5} {eggs: 7}] | table", None)?;
+    let lines: Vec<_> = stdout.lines().collect();
+
+    // The header row comes after the top border, the rows after the separator below it
+    let header = lines.get(1).ok_or("missing header row")?;
+    assert!(header.contains("spam"));
+    assert!(!header.contains("eggs"));
+
+    let second_row = lines.get(4).ok_or("missing second row")?;
+    assert!(!second_row.contains('7'));
+
+    Ok(())
+}
+
+#[test]
+fn to_md_alignment() -> TestResult {
+    run_test(
+        r#"([[name size]; [spam 10] [eggs 2.5]] | to md | lines).1"#,
+        "| --- | --: |",
+    )
+}
+
+#[test]
+fn to_md_pretty() -> TestResult {
+    run_test(
+        r#"([[name size]; [spam 10] [bacon 2.5]] | to md --pretty | lines).2"#,
+        "| spam  |   10 |",
+    )
+}
+
+#[test]
+fn to_md_list() -> TestResult {
+    run_test(r#"([spam eggs] | to md | lines).1"#, "* eggs")
+}
+
+#[test]
+fn to_html() -> TestResult {
+    run_test(
+        r#"([[name size]; ["<spam>" 10]] | to html | lines).5"#,
+        "<tr><td>&lt;spam&gt;</td><td>10</td></tr>",
+    )
+}
+
+#[test]
+fn to_html_styled() -> TestResult {
+    run_test(
+        r#"([[size]; [10]] | to html --styled | lines).5"#,
+        r#"<tr><td style="border: 1px solid #ccc; padding: 4px 8px; text-align: right">10</td></tr>"#,
+    )
+}
+
+#[test]
+fn from_ssv() -> TestResult {
+    run_test(
+        r#"("NAME          STATUS
+spam service  Up 2 hours
+eggs          Exited" | from ssv | where NAME == eggs).0.STATUS"#,
+        "Exited",
+    )
+}
+
+#[test]
+fn from_ssv_aligned() -> TestResult {
+    run_test(
+        r#"("NAME   SIZE  OWNER
+spam     10
+eggs    200  root" | from ssv --aligned-columns).0.SIZE"#,
+        "10",
+    )
+}
+
+#[test]
+fn from_ssv_noheaders_minimum_spaces() -> TestResult {
+    run_test(
+        r#"("spam  10   first
+eggs  200   second" | from ssv --noheaders --minimum-spaces 3).1.column1"#,
+        "eggs  200",
+    )
+}
+
+#[test]
+fn detect_columns() -> TestResult {
+    run_test(
+        r#"("  PID TTY   CMD
+    1 ?     systemd --switched-root
+12345 pts/0 bash" | detect columns).0.CMD"#,
+        "systemd --switched-root",
+    )
+}
+
+#[test]
+fn detect_columns_right_aligned() -> TestResult {
+    run_test(
+        r#"("  PID TTY   CMD
+    1 ?     systemd --switched-root
+12345 pts/0 bash" | detect columns | where PID > 100).0.TTY"#,
+        "pts/0",
+    )
+}
+
+#[test]
+fn detect_columns_skip_noheaders() -> TestResult {
+    run_test(
+        r#"("total 2
+spam  10
+eggs  200" | detect columns --skip 1 --noheaders).1.column2"#,
+        "200",
+    )
+}
+
+#[test]
+fn detect_columns_spaced_names() -> TestResult {
+    run_test(
+        r#"("CONTAINER ID   IMAGE    STATUS
+4f2a1b3c5d6e   ubuntu   Up 2 hours" | detect columns).0."CONTAINER ID""#,
+        "4f2a1b3c5d6e",
+    )
+}
+
+#[test]
+fn detect_columns_spaced_last_name() -> TestResult {
+    run_test(
+        r#"("Filesystem  Size Mounted on
+/dev/sda1    20G /
+/dev/sda2    50G /home/spam" | detect columns).1."Mounted on""#,
+        "/home/spam",
+    )
+}
+
+#[test]
+fn detect_columns_short_line() -> TestResult {
+    run_test(
+        r#""NAME SIZE OWNER
+spam   10
+eggs  200 root" | detect columns | to json --raw"#,
+        r#"[{"NAME":"spam","SIZE":10,"OWNER":null},{"NAME":"eggs","SIZE":200,"OWNER":"root"}]"#,
+    )
+}
+
+#[test]
+fn parse_pattern() -> TestResult {
+    run_test(
+        r#"("alice@example.com:22
+bob@host:8080" | parse "{user}@{host}:{port}").1.port"#,
+        "8080",
+    )
+}
+
+#[test]
+fn parse_pattern_skips_other_lines() -> TestResult {
+    run_test(
+        r#"("alice@example.com:22
+not a login
+bob@host:8080" | parse "{user}@{host}:{port}" | length)"#,
+        "2",
+    )
+}
+
+#[test]
+fn parse_regex() -> TestResult {
+    run_test(
+        r#"("alice@example.com:22" | parse --regex '(?P<user>\w+)@(\w+)').0.capture2"#,
+        "example",
+    )
+}
+
+#[test]
+fn parse_strict() -> TestResult {
+    fail_test(
+        r#""alice@example.com:22
+not a login" | parse --strict "{user}@{host}:{port}" | to nuon"#,
+        "line 2 doesn't match",
+    )
+}
+
+#[test]
+fn parse_unclosed_column() -> TestResult {
+    fail_test(r#""a" | parse "{user""#, "without a closing")
+}
+
+#[test]
+fn sort_by() -> TestResult {
+    run_test(
+        r#"([[name size]; [spam 10] [eggs 2] [bacon 7]] | sort-by size).0.name"#,
+        "eggs",
+    )
+}
+
+#[test]
+fn sort_by_reverse_insensitive() -> TestResult {
+    run_test(
+        r#"([[name]; [spam] [Eggs] [bacon]] | sort-by name --reverse --insensitive).1.name"#,
+        "Eggs",
+    )
+}
+
+#[test]
+fn sort_by_missing_column_last() -> TestResult {
+    run_test(r#"([{a: 3} {b: 1} {a: 1}] | sort-by a --reverse).2.b"#, "1")
+}
+
+#[test]
+fn sort_by_values() -> TestResult {
+    run_test(r#"([3 1 2] | sort-by).0"#, "1")
+}
+
+#[test]
+fn uniq() -> TestResult {
+    run_test(r#"[1 2 1 3 2 1] | uniq | length"#, "3")
+}
+
+#[test]
+fn uniq_count() -> TestResult {
+    run_test(r#"([a b a] | uniq --count).0.count"#, "2")
+}
+
+#[test]
+fn uniq_stream() -> TestResult {
+    run_test(
+        r#"("b
+a
+b" | lines | uniq).1"#,
+        "a",
+    )
+}
+
+#[test]
+fn uniq_nested_lists() -> TestResult {
+    run_test(r#"[[1 2] [3] [1 2]] | uniq | length"#, "2")
+}
+
+#[test]
+fn list_equality_is_by_value() -> TestResult {
+    run_test(r#"[0.0 "spam"] == [-0.0 "spam"]"#, "true")
+}
+
+#[test]
+fn group_by() -> TestResult {
+    run_test(
+        r#"([[name kind]; [spam food] [rock stone] [eggs food]] | group-by kind).food.1.name"#,
+        "eggs",
+    )
+}
+
+#[test]
+fn group_by_block() -> TestResult {
+    run_test(
+        r#"([[name size]; [spam 10] [eggs 2]] | group-by { if $it.size > 5 { "big" } else { "small" } }).small.0.name"#,
+        "eggs",
+    )
+}
+
+#[test]
+fn group_by_value() -> TestResult {
+    run_test(
+        r#"([[name size]; [spam 1] [eggs 1.5] [bacon 1]] | group-by size | to nuon)"#,
+        r#"{"1": [[name, size]; ["spam", 1], ["bacon", 1]], "1.5": [[name, size]; ["eggs", 1.5]]}"#,
+    )
+}
+
+#[test]
+fn group_by_same_name() -> TestResult {
+    fail_test(
+        r#"[[name size]; [spam 1] [eggs "1"]] | group-by size"#,
+        "more than one group would be named '1'",
+    )
+}
+
+#[test]
+fn group_by_missing_and_empty() -> TestResult {
+    fail_test(
+        r#"[{a: ""} {b: 1}] | group-by a"#,
+        "more than one group would be named ''",
+    )
+}
+
+#[test]
+fn group_by_missing_column() -> TestResult {
+    run_test(
+        r#"([{a: x} {b: 1}] | group-by a | to nuon)"#,
+        r#"{x: [[a]; ["x"]], "": [[b]; [1]]}"#,
+    )
+}